@@ -1,4 +1,19 @@
-import { Connection, Transaction, PublicKey, TransactionInstruction, ComputeBudgetProgram } from '@solana/web3.js';
+import {
+  Connection,
+  Transaction,
+  PublicKey,
+  TransactionInstruction,
+  ComputeBudgetProgram,
+  AddressLookupTableAccount,
+  TransactionMessage,
+  VersionedTransaction,
+  PACKET_DATA_SIZE,
+} from '@solana/web3.js';
+import {
+  createAssociatedTokenAccountIdempotentInstruction,
+  getAssociatedTokenAddressSync,
+  TOKEN_PROGRAM_ID,
+} from '@solana/spl-token';
 
 /**
  * Enhanced transaction verification and error handling.
@@ -196,4 +211,166 @@ export async function sendTransaction(
     "No compatible transaction method found for this wallet. " +
     "Please try a different wallet like Phantom or Solflare."
   );
-}
\ No newline at end of file
+}
+
+/**
+ * Token account the transaction should make sure exists before its instructions run
+ */
+export interface AtaRequirement {
+  owner: PublicKey;
+  mint: PublicKey;
+  tokenProgramId?: PublicKey;
+}
+
+/**
+ * Options for buildTransaction
+ */
+export interface TransactionBuildOptions {
+  /** Compute unit limit; omit to keep the runtime default (200k per instruction) */
+  computeUnitLimit?: number;
+  /** Priority fee in micro-lamports per compute unit; omit or 0 for no priority fee */
+  computeUnitPriceMicroLamports?: number;
+  /** Address lookup tables to compress the account list (routed swaps touch many pools) */
+  lookupTables?: PublicKey[];
+  /** Associated token accounts created idempotently ahead of the instructions */
+  createAtas?: AtaRequirement[];
+}
+
+/**
+ * Instructions creating each required associated token account if it does not exist yet
+ * Uses the idempotent ATA instruction, so an existing account is left untouched
+ * 
+ * @param payer The account paying rent for any account created
+ * @param requirements Owner and mint of each account
+ * @returns One instruction per distinct account
+ */
+export function ataPreInstructions(
+  payer: PublicKey,
+  requirements: AtaRequirement[]
+): TransactionInstruction[] {
+  const seen = new Set<string>();
+  const instructions: TransactionInstruction[] = [];
+  for (const { owner, mint, tokenProgramId = TOKEN_PROGRAM_ID } of requirements) {
+    const ata = getAssociatedTokenAddressSync(mint, owner, true, tokenProgramId);
+    if (seen.has(ata.toBase58())) {
+      continue;
+    }
+    seen.add(ata.toBase58());
+    instructions.push(
+      createAssociatedTokenAccountIdempotentInstruction(payer, ata, owner, mint, tokenProgramId)
+    );
+  }
+  return instructions;
+}
+
+/**
+ * Compute budget instructions for a CU limit and priority fee
+ * 
+ * @param options Build options carrying the limit and price
+ * @returns Zero, one or two compute budget instructions
+ */
+export function computeBudgetInstructions(options: TransactionBuildOptions): TransactionInstruction[] {
+  const instructions: TransactionInstruction[] = [];
+  if (options.computeUnitLimit) {
+    instructions.push(ComputeBudgetProgram.setComputeUnitLimit({ units: options.computeUnitLimit }));
+  }
+  if (options.computeUnitPriceMicroLamports) {
+    instructions.push(
+      ComputeBudgetProgram.setComputeUnitPrice({ microLamports: options.computeUnitPriceMicroLamports })
+    );
+  }
+  return instructions;
+}
+
+/**
+ * Fetch address lookup tables, failing on any that does not exist
+ * 
+ * @param connection Solana connection to use
+ * @param addresses Lookup table addresses
+ * @returns The resolved tables, in order
+ */
+export async function resolveLookupTables(
+  connection: Connection,
+  addresses: PublicKey[]
+): Promise<AddressLookupTableAccount[]> {
+  const tables = await Promise.all(addresses.map(address => connection.getAddressLookupTable(address)));
+  return tables.map((table, index) => {
+    if (!table.value) {
+      throw new Error(`Address lookup table ${addresses[index].toBase58()} not found`);
+    }
+    return table.value;
+  });
+}
+
+/**
+ * Assemble a complete v0 transaction: compute budget, ATA pre-creation, then the
+ * instructions, with the account list compressed through any lookup tables
+ * 
+ * Fails before signing when the transaction is over the packet size limit, so a routed
+ * swap without lookup tables surfaces a clear error instead of a wallet or RPC rejection.
+ * 
+ * @param connection Solana connection to use for blockhash and lookup tables
+ * @param payer Fee payer
+ * @param instructions Program instructions to include
+ * @param options Compute budget, lookup table and ATA options
+ * @returns An unsigned versioned transaction and the block height it expires at
+ */
+export async function buildTransaction(
+  connection: Connection,
+  payer: PublicKey,
+  instructions: TransactionInstruction[],
+  options: TransactionBuildOptions = {}
+): Promise<{ transaction: VersionedTransaction; lastValidBlockHeight: number }> {
+  const lookupTables = await resolveLookupTables(connection, options.lookupTables ?? []);
+  const { blockhash, lastValidBlockHeight } = await connection.getLatestBlockhash();
+
+  const message = new TransactionMessage({
+    payerKey: payer,
+    recentBlockhash: blockhash,
+    instructions: [
+      ...computeBudgetInstructions(options),
+      ...ataPreInstructions(payer, options.createAtas ?? []),
+      ...instructions,
+    ],
+  }).compileToV0Message(lookupTables);
+
+  const transaction = new VersionedTransaction(message);
+  const size = transaction.serialize().length;
+  if (size > PACKET_DATA_SIZE) {
+    throw new Error(
+      `Transaction is ${size} bytes, over the ${PACKET_DATA_SIZE}-byte limit; ` +
+      `pass address lookup tables or split the instructions`
+    );
+  }
+
+  return { transaction, lastValidBlockHeight };
+}
+
+/**
+ * Estimate the compute units a set of instructions uses by simulating them
+ * The result can be padded and passed as computeUnitLimit to buildTransaction
+ * 
+ * @param connection Solana connection to use
+ * @param payer Fee payer
+ * @param instructions Program instructions to measure
+ * @param options Lookup table and ATA options; the compute budget options are ignored
+ * @returns Units consumed, or undefined when the simulation fails
+ */
+export async function estimateComputeUnits(
+  connection: Connection,
+  payer: PublicKey,
+  instructions: TransactionInstruction[],
+  options: TransactionBuildOptions = {}
+): Promise<number | undefined> {
+  const { transaction } = await buildTransaction(connection, payer, instructions, {
+    ...options,
+    computeUnitLimit: 1_400_000,
+    computeUnitPriceMicroLamports: undefined,
+  });
+  const simulation = await connection.simulateTransaction(transaction, { sigVerify: false });
+  if (simulation.value.err) {
+    console.error("Compute unit estimate failed:", simulation.value.err);
+    return undefined;
+  }
+  return simulation.value.unitsConsumed;
+}
@@ -0,0 +1,13 @@
+//! Legacy processors kept for reference
+//!
+//! The files under `legacy/` are earlier versions of the staking and swap programs. They are
+//! not compiled, so they can never end up in a deployed binary or clash with the live
+//! processor's `entrypoint!`. The live processors are in the `multi-hub-swap` crate.
+//!
+//! - `swap_lib.rs`: original swap program entry (referenced a missing `multi_hub_swap` module)
+//! - `manual_serialization.rs`: first manual-serialization version of the multi-hub swap
+//! - `staking_orig.rs`, `staking_working.rs`, `staking_display_fix.rs`: staking program snapshots
+//! - `precision_fix_9260.rs`: notes for the YOS display normalization fix
+//!
+//! Their `ProgramState` structs are older layouts of the live state; `multi_hub_swap::state`
+//! holds the only compiled definitions and reads those layouts.
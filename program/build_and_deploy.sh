@@ -1,19 +1,21 @@
 #!/bin/bash
 
+# Builds and deploys the staking program (processor-staking feature of the multi-hub-swap crate)
+
 # Exit on error
 set -e
 
 echo "Building Solana program..."
-cargo build
+cargo build -p multi-hub-swap --no-default-features --features processor-staking
 
 echo "Converting to BPF..."
-cargo build --target bpfel-unknown-unknown --release
+cargo build-sbf --manifest-path multi-hub-swap/Cargo.toml --no-default-features --features processor-staking
 
 echo "Preparing deployment..."
 mkdir -p target/deploy
-cp target/bpfel-unknown-unknown/release/stake.so target/deploy/
+cp target/deploy/multi_hub_swap.so target/deploy/stake.so
 
 echo "Deploying to Solana devnet..."
 solana program deploy --program-id 6yw2VmZEJw5QkSG7svt4QL8DyCMxUKRtLqqBPTzLZHT6 target/deploy/stake.so
 
-echo "Deployment completed successfully."
\ No newline at end of file
+echo "Deployment completed successfully."
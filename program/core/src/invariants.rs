@@ -0,0 +1,91 @@
+//! Protocol invariants as pure predicates
+//!
+//! Each invariant is a plain function of amounts, so a model checker (Kani, Prusti) can
+//! state it as a proof obligation without the Solana runtime. Program code asserts them
+//! through [`require`]: a violation trips a `debug_assert!` in test and debug builds, so it
+//! stops at the faulty call, and comes back as `Err` in release builds for the caller to
+//! turn into a program error.
+
+use crate::pool_invariant_holds;
+use crate::safe_math::mul_div_u128;
+
+/// Invariant that failed a [`require`]
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Invariant {
+    /// Percentage rates taken from one amount add up to at most 100%
+    FeeSum,
+    /// A swap never lowers the pool's constant product
+    ReserveConservation,
+    /// An accrual never distributes more than the budget for the elapsed time
+    EmissionCap,
+    /// Removing a contribution never takes a total below zero
+    NonNegativeContribution,
+}
+
+/// `Ok` when `holds`; otherwise a debug assertion in test and debug builds, `Err` in release
+pub fn require(holds: bool, invariant: Invariant) -> Result<(), Invariant> {
+    debug_assert!(holds, "{:?} invariant violated", invariant);
+    if holds {
+        Ok(())
+    } else {
+        Err(invariant)
+    }
+}
+
+/// Whether percentage rates of the same amount sum to at most 100
+pub fn fee_sum_within_total(rates_percent: &[u64]) -> bool {
+    rates_percent
+        .iter()
+        .try_fold(0u64, |sum, rate| sum.checked_add(*rate))
+        .is_some_and(|sum| sum <= 100)
+}
+
+/// Whether a swap conserved the pool reserves (k never decreases)
+pub fn reserves_conserved(
+    reserve_in_before: u64,
+    reserve_out_before: u64,
+    reserve_in_after: u64,
+    reserve_out_after: u64,
+) -> bool {
+    pool_invariant_holds(reserve_in_before, reserve_out_before, reserve_in_after, reserve_out_after)
+}
+
+/// Whether `distributed` stays within `budget` per `period` pro-rated over `elapsed`
+pub fn emission_within_cap(distributed: u128, budget: u64, elapsed: u64, period: u64) -> bool {
+    match mul_div_u128(budget as u128, elapsed as u128, period as u128) {
+        Some(cap) => distributed <= cap,
+        None => distributed == 0,
+    }
+}
+
+/// Whether `removed` can come out of `total` without going below zero
+pub fn contribution_non_negative(total: u64, removed: u64) -> bool {
+    removed <= total
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn fee_sum_rejects_overflowing_rates() {
+        assert!(fee_sum_within_total(&[20, 5, 0]));
+        assert!(fee_sum_within_total(&[100]));
+        assert!(!fee_sum_within_total(&[60, 41]));
+        assert!(!fee_sum_within_total(&[u64::MAX, 2]));
+    }
+
+    #[test]
+    fn emission_cap_is_pro_rated() {
+        let week = 604_800;
+        assert!(emission_within_cap(500, 1_000, week / 2, week));
+        assert!(!emission_within_cap(501, 1_000, week / 2, week));
+        assert!(!emission_within_cap(1, 1_000, week, 0));
+    }
+
+    #[test]
+    fn require_passes_holding_invariants() {
+        assert_eq!(require(contribution_non_negative(10, 10), Invariant::NonNegativeContribution), Ok(()));
+        assert_eq!(require(reserves_conserved(100, 100, 110, 91), Invariant::ReserveConservation), Ok(()));
+    }
+}
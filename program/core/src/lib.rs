@@ -0,0 +1,253 @@
+//! Pure swap and pool math for the multi-hub swap program
+//!
+//! Nothing here touches accounts or the Solana runtime, so the same functions can be used
+//! on-chain, in off-chain quoting, and in host-side tests. Multiply-then-divide goes through
+//! [`safe_math`], so inputs anywhere up to u64::MAX are quoted exactly.
+
+pub mod invariants;
+pub mod safe_math;
+
+use safe_math::{fee_bps_of, mul_div, mul_div_u128};
+
+/// Calculate the output of a swap against a constant product (x * y = k) pool
+/// reserve_in must be the input-side balance before the user's tokens arrive. None when
+/// nothing comes into an empty input reserve, which has no price
+pub fn calculate_swap_output(amount_in: u64, reserve_in: u64, reserve_out: u64) -> Option<u64> {
+    let amount_out = mul_div_u128(amount_in as u128, reserve_out as u128, (reserve_in as u128) + (amount_in as u128))?;
+    // The output is below reserve_out, so it always fits back in a u64
+    u64::try_from(amount_out).ok()
+}
+
+/// Calculate the output of a swap against an internal pool, after the pool fee
+/// The fee (swap_fee_rate, in percent) stays in the input reserve and accrues to LPs
+pub fn calculate_pool_swap_output(amount_in: u64, reserve_in: u64, reserve_out: u64, fee_rate: u64) -> Option<u64> {
+    calculate_pool_swap_output_bps(amount_in, reserve_in, reserve_out, fee_rate.saturating_mul(100))
+}
+
+/// Calculate the output of a swap against an internal pool, with the fee in basis points
+/// Used where a fee discount makes the rate finer than a whole percent. The fee rounds up
+/// and the output down, per the rounding policy in [`safe_math`]
+pub fn calculate_pool_swap_output_bps(amount_in: u64, reserve_in: u64, reserve_out: u64, fee_bps: u64) -> Option<u64> {
+    let amount_in_after_fee = amount_in - fee_bps_of(amount_in, fee_bps);
+    calculate_swap_output(amount_in_after_fee, reserve_in, reserve_out)
+}
+
+/// Integer square root (floor), used for the initial LP supply of a pool
+pub fn integer_sqrt(value: u128) -> u128 {
+    if value < 2 {
+        return value;
+    }
+    let mut x = value;
+    let mut y = x.div_ceil(2);
+    while y < x {
+        x = y;
+        y = (x + value / x) / 2;
+    }
+    x
+}
+
+/// LP tokens minted for a one-sided deposit of `amount` into a reserve of `reserve`
+/// Priced as if half the deposit were swapped to the other side at the pool price with no
+/// fee: lp_supply * (sqrt((reserve + amount) / reserve) - 1), rounded down. An empty pool has
+/// no price and mints nothing; None when reserve * (reserve + amount) does not fit a u128
+pub fn single_sided_lp_amount(amount: u64, reserve: u64, lp_supply: u64) -> Option<u64> {
+    if reserve == 0 || lp_supply == 0 {
+        return Some(0);
+    }
+    let reserve = reserve as u128;
+    let grown = integer_sqrt(reserve.checked_mul(reserve + amount as u128)?);
+    mul_div_u128(lp_supply as u128, grown - reserve, reserve).map(|lp| lp.min(u64::MAX as u128) as u64)
+}
+
+/// Product of the two reserves of a constant product pool
+pub fn constant_product(reserve_a: u64, reserve_b: u64) -> u128 {
+    (reserve_a as u128) * (reserve_b as u128)
+}
+
+/// Whether a swap kept the constant product invariant (k never decreases)
+pub fn pool_invariant_holds(
+    reserve_in_before: u64,
+    reserve_out_before: u64,
+    reserve_in_after: u64,
+    reserve_out_after: u64,
+) -> bool {
+    constant_product(reserve_in_after, reserve_out_after) >= constant_product(reserve_in_before, reserve_out_before)
+}
+
+/// Price impact of a swap, in basis points: how far the execution price falls below the spot price
+/// For a constant product pool this is amount_in / (reserve_in + amount_in), before fees.
+/// None for an empty swap against an empty reserve
+pub fn price_impact_bps(amount_in: u64, reserve_in: u64) -> Option<u64> {
+    let impact = mul_div_u128(amount_in as u128, 10_000, (reserve_in as u128) + (amount_in as u128))?;
+    u64::try_from(impact).ok()
+}
+
+/// Largest input whose price impact stays within max_impact_bps
+/// A cap of 10_000 bps or more places no limit on the input
+pub fn max_input_within_impact(reserve_in: u64, max_impact_bps: u64) -> u64 {
+    if max_impact_bps >= 10_000 {
+        return u64::MAX;
+    }
+    mul_div(reserve_in, max_impact_bps, 10_000 - max_impact_bps).unwrap_or(u64::MAX)
+}
+
+/// Phantom shares and tokens every share vault (xYOS) is priced with
+/// A donation to a near-empty vault mostly accrues to these virtual shares, so inflating the
+/// share price costs the donor far more than a later depositor can lose to rounding
+pub const VIRTUAL_VAULT_OFFSET: u64 = 1_000;
+
+/// Vault shares minted for a deposit into a share vault (xYOS), rounded down
+/// Priced at (total_shares + offset) / (vault_balance + offset), so the first deposit into an
+/// empty vault mints one share per token. None when the shares would not fit a u64
+pub fn shares_for_deposit(amount: u64, total_shares: u64, vault_balance: u64) -> Option<u64> {
+    let offset = VIRTUAL_VAULT_OFFSET as u128;
+    let shares = mul_div_u128(amount as u128, total_shares as u128 + offset, vault_balance as u128 + offset)?;
+    u64::try_from(shares).ok()
+}
+
+/// Tokens paid out for redeeming shares of a share vault (xYOS), rounded down
+/// The inverse of [`shares_for_deposit`], with the same virtual offset; never more than the vault
+pub fn vault_amount_for_shares(shares: u64, total_shares: u64, vault_balance: u64) -> u64 {
+    let offset = VIRTUAL_VAULT_OFFSET as u128;
+    mul_div_u128(shares as u128, vault_balance as u128 + offset, total_shares as u128 + offset)
+        .map_or(vault_balance, |amount| amount.min(vault_balance as u128) as u64)
+}
+
+/// Tokens paid out for redeeming `shares` of a `total_shares` claim on `vault_balance`, rounded down
+pub fn amount_for_shares(shares: u64, total_shares: u64, vault_balance: u64) -> u64 {
+    match mul_div(shares, vault_balance, total_shares) {
+        Some(amount) => amount,
+        // Redeeming more shares than exist is capped at the whole vault
+        None if total_shares > 0 => vault_balance,
+        None => 0,
+    }
+}
+
+/// Distance of `price` from `reference`, in basis points of the reference
+pub fn price_deviation_bps(price: u64, reference: u64) -> u64 {
+    if reference == 0 {
+        return u64::MAX;
+    }
+    mul_div(price.abs_diff(reference), 10_000, reference).unwrap_or(u64::MAX)
+}
+
+/// Smallest output within max_slippage_bps of the spot price
+/// The spot output values amount_in at reserve_out / reserve_in, before any price impact.
+/// None when the input reserve is empty and has no spot price. A minimum above u64::MAX
+/// is capped there
+pub fn min_output_for_slippage(amount_in: u64, reserve_in: u64, reserve_out: u64, max_slippage_bps: u64) -> Option<u64> {
+    let spot_output = mul_div_u128(amount_in as u128, reserve_out as u128, reserve_in as u128)?;
+    let min_output = mul_div_u128(spot_output, 10_000u128.saturating_sub(max_slippage_bps as u128), 10_000)?;
+    Some(u64::try_from(min_output).unwrap_or(u64::MAX))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn single_sided_deposit_is_priced_as_a_balanced_one() {
+        // Quadrupling one reserve doubles k's square root, so the supply doubles
+        assert_eq!(single_sided_lp_amount(3_000_000, 1_000_000, 500_000), Some(500_000));
+        // A small deposit mints just under half its share of the reserve
+        assert_eq!(single_sided_lp_amount(10_000, 1_000_000, 1_000_000), Some(4_987));
+        assert_eq!(single_sided_lp_amount(10_000, 0, 1_000_000), Some(0));
+        assert_eq!(single_sided_lp_amount(u64::MAX, u64::MAX, 1), None);
+    }
+
+    #[test]
+    fn swap_quotes_stay_exact_at_u64_max() {
+        // Any product above u64 used to be the common case for large treasuries
+        assert_eq!(calculate_swap_output(u64::MAX, u64::MAX, u64::MAX), Some(u64::MAX / 2));
+        assert_eq!(calculate_swap_output(u64::MAX, 1, u64::MAX), Some(u64::MAX - 1));
+        assert_eq!(calculate_swap_output(1, u64::MAX, u64::MAX), Some(0));
+        assert_eq!(calculate_swap_output(u64::MAX, 0, u64::MAX), Some(u64::MAX));
+        assert_eq!(calculate_swap_output(0, 0, u64::MAX), None);
+        assert_eq!(calculate_pool_swap_output(u64::MAX, u64::MAX, u64::MAX, 0), Some(u64::MAX / 2));
+        assert_eq!(
+            calculate_pool_swap_output_bps(u64::MAX, 1, u64::MAX, 30),
+            calculate_swap_output(mul_div(u64::MAX, 9_970, 10_000).unwrap(), 1, u64::MAX),
+        );
+        assert_eq!(price_impact_bps(u64::MAX, u64::MAX), Some(5_000));
+        assert_eq!(price_impact_bps(0, 0), None);
+        assert_eq!(max_input_within_impact(u64::MAX, 9_999), u64::MAX);
+        assert_eq!(shares_for_deposit(u64::MAX, u64::MAX, u64::MAX), Some(u64::MAX));
+        assert_eq!(shares_for_deposit(u64::MAX, u64::MAX, 1), None);
+        assert_eq!(vault_amount_for_shares(u64::MAX, u64::MAX, u64::MAX), u64::MAX);
+        assert_eq!(vault_amount_for_shares(u64::MAX, 1, 7), 7);
+        assert_eq!(amount_for_shares(u64::MAX, u64::MAX, u64::MAX), u64::MAX);
+        assert_eq!(amount_for_shares(u64::MAX, 1, 7), 7);
+        assert_eq!(price_deviation_bps(u64::MAX, 1), u64::MAX);
+        assert!(pool_invariant_holds(u64::MAX - 1, u64::MAX, u64::MAX, u64::MAX));
+    }
+
+    #[test]
+    fn a_donation_cannot_steal_the_next_deposit() {
+        // The first depositor takes one share, then donates a million to the vault
+        let attacker_shares = shares_for_deposit(1, 0, 0).unwrap();
+        assert_eq!(attacker_shares, 1);
+        let vault_balance = 1 + 1_000_000;
+
+        // Without the offset the next million would round to zero shares; now it keeps almost all of it
+        let victim_shares = shares_for_deposit(1_000_000, attacker_shares, vault_balance).unwrap();
+        assert_eq!(victim_shares, 999);
+        let total_shares = attacker_shares + victim_shares;
+        let vault_balance = vault_balance + 1_000_000;
+        assert_eq!(vault_amount_for_shares(victim_shares, total_shares, vault_balance), 999_499);
+
+        // The donor gets back a thousandth of the donation
+        assert_eq!(vault_amount_for_shares(attacker_shares, total_shares, vault_balance), 1_000);
+    }
+
+    #[test]
+    fn a_donation_before_the_first_deposit_is_not_captured_by_it() {
+        // Tokens sent to an empty vault mostly stay with the virtual shares
+        let shares = shares_for_deposit(1_000, 0, 1_000_000).unwrap();
+        assert_eq!(shares, 0);
+        // A real first deposit redeems for itself, less rounding, and leaves the donation behind
+        let shares = shares_for_deposit(10_000_000, 0, 1_000_000).unwrap();
+        assert_eq!(vault_amount_for_shares(shares, shares, 11_000_000), 9_999_999);
+    }
+
+    #[test]
+    fn slippage_minimum_is_below_spot_output() {
+        assert_eq!(min_output_for_slippage(1_000, 10_000, 50_000, 100), Some(4_950));
+        assert_eq!(min_output_for_slippage(u64::MAX, 1, u64::MAX, 0), Some(u64::MAX));
+        // An empty input reserve has no spot price; it must not turn into a minimum of 0
+        assert_eq!(min_output_for_slippage(1_000, 0, 50_000, 100), None);
+        assert_eq!(min_output_for_slippage(1_000, 10_000, 50_000, 10_000), Some(0));
+    }
+
+    #[test]
+    fn fees_round_up_and_payouts_down() {
+        assert_eq!(fee_bps_of(1, 1), 1);
+        assert_eq!(fee_bps_of(10_000, 30), 30);
+        assert_eq!(fee_bps_of(10_001, 30), 31);
+        assert_eq!(fee_bps_of(5, 20_000), 5);
+        assert_eq!(safe_math::bps_of(10_001, 30), 30);
+        // A fee-paying pool never gives output for an input too small to pay the fee
+        assert_eq!(calculate_pool_swap_output_bps(1, 1_000, 1_000_000, 30), Some(0));
+    }
+
+    #[test]
+    fn small_round_trips_never_gain_a_unit() {
+        for (reserve_a, reserve_b) in [(1_000u64, 1_000u64), (7, 1_000_003), (1_000_003, 7), (u64::MAX / 3, 999)] {
+            for fee_bps in [0, 1, 30] {
+                for amount in 1..500u64 {
+                    let out = calculate_pool_swap_output_bps(amount, reserve_a, reserve_b, fee_bps).unwrap();
+                    let back = calculate_pool_swap_output_bps(out, reserve_b - out, reserve_a + amount, fee_bps).unwrap();
+                    assert!(back <= amount, "{} -> {} -> {} at {}/{} {}bps", amount, out, back, reserve_a, reserve_b, fee_bps);
+                }
+            }
+        }
+
+        // Depositing and redeeming at any vault price returns at most the deposit
+        for (total_shares, vault_balance) in [(0u64, 0u64), (0, 10), (1_000, 1_001), (3, 10), (10, 3), (999_999, 1_000_000)] {
+            for amount in 1..500u64 {
+                let shares = shares_for_deposit(amount, total_shares, vault_balance).unwrap();
+                let redeemed = vault_amount_for_shares(shares, total_shares + shares, vault_balance + amount);
+                assert!(redeemed <= amount, "{} -> {} shares -> {}", amount, shares, redeemed);
+            }
+        }
+    }
+}
@@ -0,0 +1,204 @@
+//! Overflow-free multiply-then-divide for token amounts
+//!
+//! Every `a * b / c` on amounts goes through here. u64 operands multiply in u128, which
+//! cannot overflow; u128 operands (reward accumulators, or a u64 product times another
+//! amount) multiply into a 256-bit intermediate. Results that do not fit the return type,
+//! and division by zero, come back as None instead of wrapping or panicking.
+//!
+//! Rounding policy: every division rounds in the protocol's favor. Amounts paid out
+//! (swap outputs, rewards, vault shares and redemptions, percentage splits) round down;
+//! amounts charged (fees) round up, so no input is too small to pay its fee. The dust
+//! stays in the pool or vault, and no sequence of small operations can extract a unit
+//! per iteration. Pick the direction with [`Rounding`] or use [`fee_bps_of`] for fees.
+
+/// Direction a division rounds in, per the rounding policy above
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Rounding {
+    /// Toward zero, for amounts the protocol pays out
+    Down,
+    /// Away from zero, for amounts the protocol charges
+    Up,
+}
+
+/// floor(a * b / denominator) for u64 amounts
+pub fn mul_div(a: u64, b: u64, denominator: u64) -> Option<u64> {
+    if denominator == 0 {
+        return None;
+    }
+    u64::try_from((a as u128) * (b as u128) / (denominator as u128)).ok()
+}
+
+/// ceil(a * b / denominator) for u64 amounts
+pub fn mul_div_ceil(a: u64, b: u64, denominator: u64) -> Option<u64> {
+    if denominator == 0 {
+        return None;
+    }
+    u64::try_from(((a as u128) * (b as u128)).div_ceil(denominator as u128)).ok()
+}
+
+/// a * b / denominator for u64 amounts, rounded as `rounding` says
+pub fn mul_div_rounded(a: u64, b: u64, denominator: u64, rounding: Rounding) -> Option<u64> {
+    match rounding {
+        Rounding::Down => mul_div(a, b, denominator),
+        Rounding::Up => mul_div_ceil(a, b, denominator),
+    }
+}
+
+/// floor(a * b / denominator) for u128 operands, through a 256-bit product
+pub fn mul_div_u128(a: u128, b: u128, denominator: u128) -> Option<u128> {
+    let (high, low) = widening_mul(a, b);
+    div_wide(high, low, denominator).map(|(quotient, _)| quotient)
+}
+
+/// ceil(a * b / denominator) for u128 operands, through a 256-bit product
+pub fn mul_div_u128_ceil(a: u128, b: u128, denominator: u128) -> Option<u128> {
+    let (high, low) = widening_mul(a, b);
+    let (quotient, remainder) = div_wide(high, low, denominator)?;
+    if remainder == 0 {
+        Some(quotient)
+    } else {
+        quotient.checked_add(1)
+    }
+}
+
+/// `percent`% of `amount`, rounded down; saturates at u64::MAX for percentages above 100
+pub fn percent_of(amount: u64, percent: u64) -> u64 {
+    mul_div(amount, percent, 100).unwrap_or(u64::MAX)
+}
+
+/// `bps` basis points of `amount`, rounded down; saturates at u64::MAX above 10_000 bps
+pub fn bps_of(amount: u64, bps: u64) -> u64 {
+    mul_div(amount, bps, 10_000).unwrap_or(u64::MAX)
+}
+
+/// Fee of `bps` basis points charged on `amount`, rounded up and never more than `amount`
+pub fn fee_bps_of(amount: u64, bps: u64) -> u64 {
+    mul_div_rounded(amount, bps.min(10_000), 10_000, Rounding::Up).unwrap_or(amount)
+}
+
+/// Full 256-bit product of two u128s, as (high, low) halves
+fn widening_mul(a: u128, b: u128) -> (u128, u128) {
+    const MASK: u128 = u64::MAX as u128;
+    let (a_high, a_low) = (a >> 64, a & MASK);
+    let (b_high, b_low) = (b >> 64, b & MASK);
+
+    let low_low = a_low * b_low;
+    let high_low = a_high * b_low;
+    let low_high = a_low * b_high;
+    let high_high = a_high * b_high;
+
+    // Middle 64-bit column, with the carries out of the low column
+    let middle = (low_low >> 64) + (high_low & MASK) + (low_high & MASK);
+    let low = (middle << 64) | (low_low & MASK);
+    let high = high_high + (high_low >> 64) + (low_high >> 64) + (middle >> 64);
+    (high, low)
+}
+
+/// Quotient and remainder of the 256-bit value (high, low) divided by a u128
+/// None for a zero divisor or a quotient that does not fit in u128
+fn div_wide(high: u128, low: u128, divisor: u128) -> Option<(u128, u128)> {
+    if divisor == 0 || high >= divisor {
+        return None;
+    }
+    if high == 0 {
+        return Some((low / divisor, low % divisor));
+    }
+
+    // Shift-subtract long division; the remainder stays below the divisor, with one
+    // carry bit for the shift that can push it past 128 bits
+    let mut remainder = high;
+    let mut quotient = 0u128;
+    for bit in (0..128).rev() {
+        let carry = remainder >> 127;
+        remainder = (remainder << 1) | ((low >> bit) & 1);
+        quotient <<= 1;
+        if carry == 1 || remainder >= divisor {
+            remainder = remainder.wrapping_sub(divisor);
+            quotient |= 1;
+        }
+    }
+    Some((quotient, remainder))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const BOUNDARY: [u64; 8] = [0, 1, 2, 3, u64::MAX / 2, u64::MAX - 2, u64::MAX - 1, u64::MAX];
+
+    #[test]
+    fn mul_div_matches_exact_u128_math_at_the_u64_boundary() {
+        for &a in &BOUNDARY {
+            for &b in &BOUNDARY {
+                for &denominator in &BOUNDARY {
+                    let product = (a as u128) * (b as u128);
+                    let floor = (denominator != 0).then(|| product / denominator as u128);
+                    let ceil = (denominator != 0).then(|| product.div_ceil(denominator as u128));
+                    assert_eq!(mul_div(a, b, denominator), floor.and_then(|q| u64::try_from(q).ok()));
+                    assert_eq!(mul_div_ceil(a, b, denominator), ceil.and_then(|q| u64::try_from(q).ok()));
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn mul_div_survives_products_above_u64() {
+        assert_eq!(mul_div(u64::MAX, u64::MAX, u64::MAX), Some(u64::MAX));
+        assert_eq!(mul_div(u64::MAX, 75, 100), Some(u64::MAX / 100 * 75 + (u64::MAX % 100) * 75 / 100));
+        assert_eq!(mul_div(u64::MAX, 2, 1), None);
+        assert_eq!(mul_div_ceil(u64::MAX, u64::MAX, u64::MAX - 1), None);
+    }
+
+    #[test]
+    fn widening_mul_is_exact_at_the_u128_boundary() {
+        assert_eq!(widening_mul(u128::MAX, u128::MAX), (u128::MAX - 1, 1));
+        assert_eq!(widening_mul(u128::MAX, 1), (0, u128::MAX));
+        assert_eq!(widening_mul(1 << 64, 1 << 64), (1, 0));
+        assert_eq!(widening_mul(u64::MAX as u128, u64::MAX as u128), (0, (u64::MAX as u128) * (u64::MAX as u128)));
+    }
+
+    #[test]
+    fn mul_div_u128_handles_256_bit_intermediates() {
+        assert_eq!(mul_div_u128(u128::MAX, u128::MAX, u128::MAX), Some(u128::MAX));
+        assert_eq!(mul_div_u128(u128::MAX, u128::MAX - 1, u128::MAX), Some(u128::MAX - 1));
+        assert_eq!(mul_div_u128(u128::MAX, 3, 4), Some(u128::MAX / 4 * 3 + 2));
+        assert_eq!(mul_div_u128(u128::MAX, 2, 1), None);
+        assert_eq!(mul_div_u128(1, 1, 0), None);
+        // u64 shares times a large reward accumulator, as in the pro-rata reward debt
+        let accumulator = (u64::MAX as u128) << 40;
+        assert_eq!(
+            mul_div_u128(u64::MAX as u128, accumulator, 1 << 40),
+            Some((u64::MAX as u128) * (u64::MAX as u128)),
+        );
+        assert_eq!(mul_div_u128_ceil(u128::MAX, 1, 2), Some(1 << 127));
+        assert_eq!(mul_div_u128_ceil(u128::MAX, u128::MAX, u128::MAX - 1), None);
+        assert_eq!(mul_div_u128_ceil(6, 2, 4), Some(3));
+    }
+
+    #[test]
+    fn mul_div_u128_agrees_with_u64_mul_div() {
+        for &a in &BOUNDARY {
+            for &b in &BOUNDARY {
+                for &denominator in BOUNDARY.iter().filter(|&&d| d != 0) {
+                    let wide = mul_div_u128(a as u128, b as u128, denominator as u128);
+                    assert_eq!(wide, Some((a as u128) * (b as u128) / denominator as u128));
+                    assert_eq!(wide.and_then(|q| u64::try_from(q).ok()), mul_div(a, b, denominator));
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn percentages_of_u64_max_do_not_overflow() {
+        assert_eq!(percent_of(u64::MAX, 100), u64::MAX);
+        assert_eq!(percent_of(u64::MAX, 0), 0);
+        assert_eq!(percent_of(u64::MAX, 200), u64::MAX);
+        // The 75/20/5 split of a u64::MAX amount never adds up to more than the amount
+        let split = percent_of(u64::MAX, 75)
+            .checked_add(percent_of(u64::MAX, 20))
+            .and_then(|sum| sum.checked_add(percent_of(u64::MAX, 5)));
+        assert!(split.is_some_and(|split| u64::MAX - split < 3));
+        assert_eq!(bps_of(u64::MAX, 10_000), u64::MAX);
+        assert_eq!(bps_of(u64::MAX, 1), u64::MAX / 10_000);
+    }
+}
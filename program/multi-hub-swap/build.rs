@@ -0,0 +1,733 @@
+// Build script: generates the error code table and the program IDL from the source
+//
+// src/multi_hub_swap_complete.rs is the single source of truth. This script reads:
+// - the `#[error("...")]` message and variant name of every MultiHubSwapError entry
+// - every arm of the process_instruction dispatcher: its discriminator, the
+//   instruction data it decodes, and the handler it calls
+// - the `next_account_info(accounts_iter)` lines of each handler, in order, with their
+//   trailing comments as account descriptions (`.ok()` marks an optional account), and a
+//   closing `accounts_iter.as_slice()` binding as the instruction's remaining accounts.
+//   Remaining accounts forwarded whole to another handler are listed as that handler's
+//   accounts. A handler that only forwards `accounts` to a helper is read through that
+//   helper. The
+//   build fails when a handler uses its iterator any other way, such as passing it to a
+//   helper, since the accounts that helper takes would be missing from the IDL
+// - every `fn ... -> (Pubkey, u8)` ending in `Pubkey::find_program_address`, as PDA seeds
+// - every `pub struct` with a `LEN` constant, as an account/state layout, here and in
+//   src/state.rs (except the staking program's layouts)
+//
+// Arms whose instruction data the dispatcher does not decode field by field carry an
+// annotation comment listing the fields instead:
+//     // idl-args: name: type, name: type?
+// where a trailing `?` marks an optional trailing field. `// idl-name: Name` overrides
+// the instruction name derived from the handler.
+//
+// It writes:
+// - $OUT_DIR/error_codes.rs   (table included by error_code_to_message)
+// - $OUT_DIR/error_codes.json (embedded as ERROR_CODES_JSON)
+// - $OUT_DIR/instruction_tags.rs (table included as INSTRUCTION_TAGS)
+// - $OUT_DIR/idl.json         (embedded as IDL_JSON off-chain)
+// - $OUT_DIR/anchor_idl.json  (the same in Anchor's IDL format, embedded as ANCHOR_IDL_JSON)
+// - <workspace>/target/idl/multi_hub_swap_errors.json, multi_hub_swap.json and
+//   multi_hub_swap_anchor.json (stable paths for SDKs, tests and explorers)
+// - <workspace>/target/idl/multi_hub_swap.md (the instruction reference for client authors)
+
+// Host-side tooling: a bad source file should stop the build loudly, so the crate's
+// handler lints against panics and unchecked arithmetic do not apply here
+#![allow(clippy::arithmetic_side_effects, clippy::expect_used, clippy::unwrap_used)]
+
+use std::env;
+use std::fs;
+use std::path::Path;
+
+const PROGRAM_SOURCE: &str = "src/multi_hub_swap_complete.rs";
+const STATE_SOURCE: &str = "src/state.rs";
+const ERROR_ENUM: &str = "pub enum MultiHubSwapError {";
+const DISPATCHER: &str = "match instruction_data[0] {";
+const ARGS_ANNOTATION: &str = "// idl-args:";
+const NAME_ANNOTATION: &str = "// idl-name:";
+// Address written into the Anchor IDL unless MULTI_HUB_SWAP_PROGRAM_ID is set
+const DEFAULT_PROGRAM_ID: &str = "Js9TqdpLBsF7M64ra2mYNyfbPTWwTvBUNR85wsEoSKP";
+
+struct ErrorCode {
+    code: u32,
+    name: String,
+    message: String,
+}
+
+struct Field {
+    name: String,
+    ty: String,
+    optional: bool,
+}
+
+struct Account {
+    name: String,
+    description: String,
+    optional: bool,
+    remaining: bool,
+    signer: bool,
+    writable: bool,
+}
+
+struct Instruction {
+    discriminator: u8,
+    name: String,
+    handler: String,
+    args: Vec<Field>,
+    accounts: Vec<Account>,
+}
+
+struct Seed {
+    constant: Option<String>,
+    arg: Option<String>,
+}
+
+struct Pda {
+    name: String,
+    seeds: Vec<Seed>,
+}
+
+struct Layout {
+    name: String,
+    docs: String,
+    fields: Vec<(Field, String)>,
+    has_len: bool,
+}
+
+fn parse_error_codes(source: &str) -> Vec<ErrorCode> {
+    let mut codes = Vec::new();
+    let mut in_enum = false;
+    let mut pending_message: Option<String> = None;
+    let mut next_code: u32 = 0;
+
+    for line in source.lines() {
+        let line = line.trim();
+        if !in_enum {
+            in_enum = line.starts_with(ERROR_ENUM);
+            continue;
+        }
+        if line.starts_with('}') {
+            break;
+        }
+        if let Some(rest) = line.strip_prefix("#[error(\"") {
+            if let Some(end) = rest.rfind("\")]") {
+                pending_message = Some(rest[..end].to_string());
+            }
+            continue;
+        }
+        if line.is_empty() || line.starts_with("//") || line.starts_with("#[") {
+            continue;
+        }
+
+        // Variant line, optionally with an explicit discriminant: `Name,` or `Name = 42,`
+        let variant = line.trim_end_matches(',');
+        let (name, code) = match variant.split_once('=') {
+            Some((name, value)) => (
+                name.trim().to_string(),
+                value.trim().parse().expect("error discriminant must be a u32 literal"),
+            ),
+            None => (variant.trim().to_string(), next_code),
+        };
+        let message = pending_message.take().unwrap_or_else(|| name.clone());
+        codes.push(ErrorCode { code, name, message });
+        next_code = code + 1;
+    }
+
+    codes
+}
+
+/// `process_claim_rebate` -> `ClaimRebate`
+fn instruction_name(handler: &str) -> String {
+    handler
+        .trim_start_matches("process_")
+        .split('_')
+        .map(|word| {
+            let mut chars = word.chars();
+            match chars.next() {
+                Some(first) => first.to_ascii_uppercase().to_string() + chars.as_str(),
+                None => String::new(),
+            }
+        })
+        .collect()
+}
+
+/// First `process_*(` call in a dispatcher arm
+fn arm_handler(arm: &str) -> Option<String> {
+    let start = arm.find("process_")?;
+    let rest = &arm[start..];
+    let end = rest.find('(')?;
+    Some(rest[..end].to_string())
+}
+
+/// `[a..b]` or `[a]` right after `instruction_data`, as the start offset
+fn data_offset(expr: &str) -> Option<usize> {
+    let start = expr.find("instruction_data[")? + "instruction_data[".len();
+    let digits: String = expr[start..].chars().take_while(|c| c.is_ascii_digit()).collect();
+    digits.parse().ok()
+}
+
+/// Fields decoded by a dispatcher arm, ordered by offset
+fn arm_args(arm: &str) -> Vec<Field> {
+    for line in arm.lines() {
+        if let Some((_, annotation)) = line.split_once(ARGS_ANNOTATION) {
+            return annotation
+                .split(',')
+                .filter_map(|field| field.split_once(':'))
+                .map(|(name, ty)| {
+                    let ty = ty.trim();
+                    Field {
+                        name: name.trim().to_string(),
+                        ty: ty.trim_end_matches('?').to_string(),
+                        optional: ty.ends_with('?'),
+                    }
+                })
+                .collect();
+        }
+    }
+
+    let mut args: Vec<(usize, Field)> = Vec::new();
+    for line in arm.lines() {
+        let line = line.trim();
+        for (flag, fields) in [
+            ("simulate_only", &[("simulate_only", "u8")][..]),
+            ("allow_partial", &[("allow_partial", "u8")]),
+            ("swap_recipient", &[("swap_recipient", "Pubkey")]),
+            ("swap_memo", &[("memo_len", "u8"), ("memo", "[u8; memo_len]")]),
+        ] {
+            let call = format!("{}(instruction_data, ", flag);
+            if let Some(start) = line.find(&call) {
+                let digits: String = line[start + call.len()..].chars().take_while(|c| c.is_ascii_digit()).collect();
+                if let Ok(offset) = digits.parse() {
+                    for (name, ty) in fields {
+                        args.push((offset, Field { name: name.to_string(), ty: ty.to_string(), optional: true }));
+                    }
+                }
+            }
+        }
+
+        let Some(binding) = line.strip_prefix("let ") else { continue };
+        let Some((name, expr)) = binding.split_once(" = ") else { continue };
+        let Some(offset) = data_offset(expr) else { continue };
+        let ty = if let Some((ty, _)) = expr.split_once("::from_le_bytes(") {
+            ty.to_string()
+        } else if expr.starts_with("Pubkey::new_from_array(") {
+            "Pubkey".to_string()
+        } else if expr.contains("!= 0") {
+            "bool".to_string()
+        } else {
+            "u8".to_string()
+        };
+        args.push((offset, Field { name: name.trim().to_string(), ty, optional: false }));
+    }
+
+    args.sort_by_key(|(offset, _)| *offset);
+    args.into_iter().map(|(_, field)| field).collect()
+}
+
+fn parse_instructions(source: &str) -> Vec<Instruction> {
+    let Some(start) = source.find(DISPATCHER) else { return Vec::new() };
+    let mut instructions = Vec::new();
+    let mut lines = source[start + DISPATCHER.len()..].lines();
+
+    while let Some(line) = lines.next() {
+        let trimmed = line.trim();
+        if trimmed.starts_with("_ =>") {
+            break;
+        }
+        let Some((discriminator, rest)) = trimmed.split_once(" => ") else { continue };
+        let Ok(discriminator) = discriminator.parse::<u8>() else { continue };
+
+        // Block arms run until the closing `},` at the arm's indentation
+        let mut arm = rest.to_string();
+        if rest == "{" {
+            let indent = &line[..line.len() - line.trim_start().len()];
+            let close = format!("{}}},", indent);
+            for body_line in lines.by_ref() {
+                if body_line == close {
+                    break;
+                }
+                arm.push('\n');
+                arm.push_str(body_line);
+            }
+        }
+
+        let Some(handler) = arm_handler(&arm) else { continue };
+        let name = arm
+            .lines()
+            .find_map(|line| line.split_once(NAME_ANNOTATION))
+            .map(|(_, name)| name.trim().to_string())
+            .unwrap_or_else(|| instruction_name(&handler));
+        instructions.push(Instruction {
+            discriminator,
+            name,
+            args: arm_args(&arm),
+            accounts: handler_accounts(source, &handler),
+            handler,
+        });
+    }
+
+    instructions
+}
+
+/// Stop the build unless the dispatcher's tags are unique and run from 0 without gaps
+/// A repeated tag leaves the later arm unreachable; a gap usually means an arm lost its handler
+fn verify_discriminators(instructions: &[Instruction]) {
+    let mut tags: Vec<(u8, &str)> =
+        instructions.iter().map(|instruction| (instruction.discriminator, instruction.name.as_str())).collect();
+    tags.sort();
+    for pair in tags.windows(2) {
+        if pair[0].0 == pair[1].0 {
+            panic!("instruction tag {} is used by both {} and {}", pair[0].0, pair[0].1, pair[1].1);
+        }
+    }
+    for (expected, (tag, name)) in tags.iter().enumerate() {
+        if *tag as usize != expected {
+            panic!("instruction tag {} is not dispatched; the next tag is {} ({})", expected, tag, name);
+        }
+    }
+}
+
+/// Body of a top-level fn, from its signature to the closing brace at column 0
+fn fn_body<'a>(source: &'a str, signature: &str) -> Option<&'a str> {
+    let start = source.find(signature)?;
+    let body = &source[start..];
+    let end = body.find("\n}\n").unwrap_or(body.len());
+    Some(&body[..end])
+}
+
+/// Body of the fn that parses a handler's accounts: the handler itself, or the helper it
+/// forwards `accounts` to when it parses none of its own
+fn accounts_body<'a>(source: &'a str, handler: &str) -> &'a str {
+    let body = fn_body(source, &format!("pub fn {}(", handler))
+        .or_else(|| fn_body(source, &format!("fn {}<", handler)))
+        .unwrap_or_else(|| panic!("handler {} is not a top-level fn", handler));
+    if body.contains("accounts_iter") {
+        return body;
+    }
+    let delegate = body
+        .lines()
+        .find_map(|line| {
+            let (call, _) = line.split_once("(program_id, accounts")?;
+            call.rsplit(|c: char| !c.is_ascii_alphanumeric() && c != '_').next().filter(|name| !name.is_empty())
+        })
+        .unwrap_or_else(|| panic!("{} neither parses its accounts nor forwards them to a helper", handler));
+    fn_body(source, &format!("fn {}(", delegate))
+        .or_else(|| fn_body(source, &format!("fn {}<", delegate)))
+        .unwrap_or_else(|| panic!("{} forwards its accounts to {}, which is not a top-level fn", handler, delegate))
+}
+
+fn handler_accounts(source: &str, handler: &str) -> Vec<Account> {
+    let body = accounts_body(source, handler);
+    let mut accounts = Vec::new();
+    for line in body.lines() {
+        let line = line.trim();
+        if !line.contains("accounts_iter") || line == "let accounts_iter = &mut accounts.iter();" {
+            continue;
+        }
+        let parsed = line.strip_prefix("let ").and_then(|binding| binding.split_once(" = ")).and_then(|(name, rest)| {
+            let description = rest.split_once("//").map(|(_, comment)| comment.trim()).unwrap_or("");
+            let remaining = rest.starts_with("accounts_iter.as_slice();");
+            (rest.starts_with("next_account_info(accounts_iter)") || remaining).then(|| (name.trim(), rest, description, remaining))
+        });
+        let Some((name, rest, description, remaining)) = parsed else {
+            panic!(
+                "{}: `{}` takes accounts outside `let .. = next_account_info(accounts_iter)` or \
+                 `let .. = accounts_iter.as_slice()`, so the IDL would not list them; parse them in the handler",
+                handler, line
+            );
+        };
+        // Signers are the accounts the handler checks is_signer on; programs and
+        // accounts documented as read-only are the only ones never written
+        accounts.push(Account {
+            name: name.to_string(),
+            description: description.to_string(),
+            optional: rest.contains(".ok()"),
+            remaining,
+            signer: body.contains(&format!("!{}.is_signer", name)),
+            writable: !name.contains("_program") && !description.contains("read-only"),
+        });
+    }
+    if accounts.is_empty() {
+        panic!("{} lists no accounts", handler);
+    }
+    if let Some(position) = accounts.iter().position(|account| account.remaining) {
+        if position + 1 != accounts.len() {
+            panic!("{}: remaining accounts {} must come last", handler, accounts[position].name);
+        }
+        // Remaining accounts handed whole to another handler take that handler's layout. A
+        // forwarding handler that acts for the first of them says so with "not signing"
+        let forwarded = format!("(program_id, {}", accounts[position].name);
+        let target = body.lines().find_map(|line| {
+            let (call, rest) = line.split_once(forwarded.as_str())?;
+            let whole = rest.starts_with(',') || rest.starts_with(')');
+            call.rsplit(|c: char| !c.is_ascii_alphanumeric() && c != '_').next().filter(|name| whole && !name.is_empty())
+        });
+        if let Some(target) = target {
+            let remaining = accounts.pop().expect("remaining accounts were just found");
+            let mut forwarded_accounts = handler_accounts(source, target);
+            if remaining.description.contains("not signing") {
+                forwarded_accounts[0].signer = false;
+                forwarded_accounts[0].description.push_str(" (not signing)");
+            }
+            accounts.extend(forwarded_accounts);
+        }
+    }
+    accounts
+}
+
+fn parse_pdas(source: &str) -> Vec<Pda> {
+    let mut pdas = Vec::new();
+    for line in source.lines().filter(|line| line.starts_with("fn ") && line.ends_with("-> (Pubkey, u8) {")) {
+        let name = line["fn ".len()..].split('(').next().unwrap_or_default();
+        let Some(body) = fn_body(source, line) else { continue };
+        let Some(last) = body.lines().rev().map(str::trim).find(|line| !line.is_empty()) else { continue };
+        let Some(seeds) = last
+            .strip_prefix("Pubkey::find_program_address(&[")
+            .and_then(|rest| rest.split_once("], program_id)"))
+            .map(|(seeds, _)| seeds)
+        else {
+            continue;
+        };
+
+        let seeds = seeds
+            .split(", ")
+            .map(|seed| match seed.strip_prefix("b\"").and_then(|rest| rest.strip_suffix('"')) {
+                Some(constant) => Seed { constant: Some(constant.to_string()), arg: None },
+                None => Seed {
+                    constant: None,
+                    arg: Some(seed.trim_start_matches('&').replace(".as_ref()", "").replace(".to_le_bytes()", "_le")),
+                },
+            })
+            .collect();
+        pdas.push(Pda {
+            name: name.trim_start_matches("find_").trim_end_matches("_address").to_string(),
+            seeds,
+        });
+    }
+    pdas
+}
+
+/// Every non-generic `pub struct`; those with a `LEN` constant are account/state layouts
+fn parse_structs(source: &str) -> Vec<Layout> {
+    let mut structs = Vec::new();
+    let mut docs: Vec<&str> = Vec::new();
+    let mut lines = source.lines();
+    while let Some(line) = lines.next() {
+        if let Some(comment) = line.strip_prefix("//") {
+            docs.push(comment.trim_start_matches('/').trim());
+            continue;
+        }
+        if line.starts_with("#[") {
+            continue;
+        }
+        let comment = docs.join(" ");
+        docs.clear();
+        let Some(name) = line.strip_prefix("pub struct ").and_then(|rest| rest.strip_suffix(" {")) else { continue };
+        if name.contains('<') {
+            continue;
+        }
+
+        let mut fields = Vec::new();
+        for field_line in lines.by_ref() {
+            if field_line.starts_with('}') {
+                break;
+            }
+            let Some(declaration) = field_line.trim().strip_prefix("pub ") else { continue };
+            let (declaration, comment) = declaration.split_once("//").unwrap_or((declaration, ""));
+            let Some((field, ty)) = declaration.trim().trim_end_matches(',').split_once(':') else { continue };
+            fields.push((
+                Field { name: field.trim().to_string(), ty: ty.trim().to_string(), optional: false },
+                comment.trim().to_string(),
+            ));
+        }
+        structs.push(Layout {
+            has_len: fn_body(source, &format!("impl {} {{", name)).is_some_and(|body| body.contains("pub const LEN")),
+            name: name.to_string(),
+            docs: comment,
+            fields,
+        });
+    }
+    structs
+}
+
+/// Encoded size of a field type, when fixed; nested structs are inlined
+fn type_size(ty: &str, structs: &[Layout]) -> Option<usize> {
+    match ty {
+        "u8" | "bool" => Some(1),
+        "u16" => Some(2),
+        "u32" => Some(4),
+        "u64" | "i64" => Some(8),
+        "u128" => Some(16),
+        "Pubkey" => Some(32),
+        _ if ty.starts_with('[') => {
+            let (item, count) = ty.trim_start_matches('[').trim_end_matches(']').split_once(';')?;
+            Some(type_size(item.trim(), structs)? * count.trim().parse::<usize>().ok()?)
+        }
+        _ => structs
+            .iter()
+            .find(|layout| layout.name == ty)?
+            .fields
+            .iter()
+            .map(|(field, _)| type_size(&field.ty, structs))
+            .sum(),
+    }
+}
+
+fn json_escape(value: &str) -> String {
+    value.replace('\\', "\\\\").replace('"', "\\\"")
+}
+
+fn json_list<T>(items: &[T], indent: &str, mut render: impl FnMut(&T) -> String) -> String {
+    if items.is_empty() {
+        return "[]".to_string();
+    }
+    let body: Vec<String> = items.iter().map(|item| format!("{}  {}", indent, render(item))).collect();
+    format!("[\n{}\n{}]", body.join(",\n"), indent)
+}
+
+fn render_idl(instructions: &[Instruction], pdas: &[Pda], structs: &[Layout], errors: &[ErrorCode]) -> String {
+    let layouts: Vec<&Layout> = structs.iter().filter(|layout| layout.has_len).collect();
+    let instructions = json_list(instructions, "  ", |instruction| {
+        format!(
+            "{{ \"discriminator\": {}, \"name\": \"{}\", \"handler\": \"{}\", \"args\": {}, \"accounts\": {} }}",
+            instruction.discriminator,
+            instruction.name,
+            instruction.handler,
+            json_list(&instruction.args, "    ", |arg| format!(
+                "{{ \"name\": \"{}\", \"type\": \"{}\", \"optional\": {} }}",
+                arg.name, json_escape(&arg.ty), arg.optional
+            )),
+            json_list(&instruction.accounts, "    ", |account| format!(
+                "{{ \"name\": \"{}\", \"optional\": {}, \"remaining\": {}, \"docs\": \"{}\" }}",
+                account.name, account.optional, account.remaining, json_escape(&account.description)
+            )),
+        )
+    });
+    let pdas = json_list(pdas, "  ", |pda| {
+        format!(
+            "{{ \"name\": \"{}\", \"seeds\": {} }}",
+            pda.name,
+            json_list(&pda.seeds, "    ", |seed| match (&seed.constant, &seed.arg) {
+                (Some(constant), _) => format!("{{ \"kind\": \"const\", \"value\": \"{}\" }}", json_escape(constant)),
+                (_, arg) => format!("{{ \"kind\": \"arg\", \"path\": \"{}\" }}", json_escape(arg.as_deref().unwrap_or_default())),
+            }),
+        )
+    });
+    let layouts = json_list(&layouts, "  ", |layout| {
+        // Offsets stop at the first variable-length field
+        let mut offset = Some(0);
+        let fields = json_list(&layout.fields, "    ", |(field, comment)| {
+            let size = type_size(&field.ty, structs);
+            let rendered = format!(
+                "{{ \"name\": \"{}\", \"type\": \"{}\", \"offset\": {}, \"size\": {}, \"docs\": \"{}\" }}",
+                field.name,
+                json_escape(&field.ty),
+                offset.map_or("null".to_string(), |offset| offset.to_string()),
+                size.map_or("null".to_string(), |size| size.to_string()),
+                json_escape(comment),
+            );
+            offset = offset.zip(size).map(|(offset, size)| offset + size);
+            rendered
+        });
+        format!("{{ \"name\": \"{}\", \"fields\": {} }}", layout.name, fields)
+    });
+    let errors = json_list(errors, "  ", |error| {
+        format!(
+            "{{ \"code\": {}, \"name\": \"{}\", \"msg\": \"{}\" }}",
+            error.code,
+            json_escape(&error.name),
+            json_escape(&error.message)
+        )
+    });
+
+    format!(
+        "{{\n  \"name\": \"multi_hub_swap\",\n  \"version\": \"{}\",\n  \"instructions\": {},\n  \"pdas\": {},\n  \"layouts\": {},\n  \"errors\": {}\n}}\n",
+        env::var("CARGO_PKG_VERSION").unwrap_or_default(),
+        instructions,
+        pdas,
+        layouts,
+        errors,
+    )
+}
+
+/// Anchor IDL type for a source type
+fn anchor_type(ty: &str) -> String {
+    match ty {
+        "Pubkey" => "\"pubkey\"".to_string(),
+        "u8" | "u16" | "u32" | "u64" | "u128" | "i64" | "bool" => format!("\"{}\"", ty),
+        _ if ty.starts_with("[u8;") => "\"bytes\"".to_string(),
+        _ if ty.starts_with('[') => {
+            let (item, count) = ty.trim_start_matches('[').trim_end_matches(']').split_once(';').unwrap_or((ty, "0"));
+            format!("{{ \"array\": [{}, {}] }}", anchor_type(item.trim()), count.trim())
+        }
+        _ => match ty.strip_prefix("Vec<").and_then(|rest| rest.strip_suffix('>')) {
+            Some(item) => format!("{{ \"vec\": {} }}", anchor_type(item)),
+            None => format!("{{ \"defined\": {{ \"name\": \"{}\" }} }}", ty),
+        },
+    }
+}
+
+/// Anchor (0.30 spec) IDL of the same program, so Anchor-aware wallets and explorers can
+/// decode it. Instruction discriminators are the native 1-byte tags; accounts have no
+/// discriminator. Optional trailing flags are listed as plain fields
+fn render_anchor_idl(instructions: &[Instruction], structs: &[Layout], errors: &[ErrorCode]) -> String {
+    let address = env::var("MULTI_HUB_SWAP_PROGRAM_ID").unwrap_or_else(|_| DEFAULT_PROGRAM_ID.to_string());
+    let accounts: Vec<&Layout> = structs
+        .iter()
+        .filter(|layout| layout.has_len && !layout.docs.contains("return data"))
+        .collect();
+
+    let instructions = json_list(instructions, "  ", |instruction| {
+        // Anchor clients pass remaining accounts outside the IDL's account list
+        let fixed_accounts: Vec<&Account> = instruction.accounts.iter().filter(|account| !account.remaining).collect();
+        format!(
+            "{{ \"name\": \"{}\", \"discriminator\": [{}], \"accounts\": {}, \"args\": {} }}",
+            instruction.handler.trim_start_matches("process_"),
+            instruction.discriminator,
+            json_list(&fixed_accounts, "    ", |account| format!(
+                "{{ \"name\": \"{}\", \"writable\": {}, \"signer\": {}, \"optional\": {}, \"docs\": [\"{}\"] }}",
+                account.name, account.writable, account.signer, account.optional, json_escape(&account.description)
+            )),
+            json_list(&instruction.args, "    ", |arg| format!(
+                "{{ \"name\": \"{}\", \"type\": {} }}",
+                arg.name, anchor_type(&arg.ty)
+            )),
+        )
+    });
+    let account_list = json_list(&accounts, "  ", |layout| {
+        format!("{{ \"name\": \"{}\", \"discriminator\": [] }}", layout.name)
+    });
+    let types = json_list(structs, "  ", |layout| {
+        format!(
+            "{{ \"name\": \"{}\", \"docs\": [\"{}\"], \"type\": {{ \"kind\": \"struct\", \"fields\": {} }} }}",
+            layout.name,
+            json_escape(&layout.docs),
+            json_list(&layout.fields, "    ", |(field, comment)| format!(
+                "{{ \"name\": \"{}\", \"docs\": [\"{}\"], \"type\": {} }}",
+                field.name, json_escape(comment), anchor_type(&field.ty)
+            )),
+        )
+    });
+    let errors = json_list(errors, "  ", |error| {
+        format!(
+            "{{ \"code\": {}, \"name\": \"{}\", \"msg\": \"{}\" }}",
+            error.code,
+            json_escape(&error.name),
+            json_escape(&error.message)
+        )
+    });
+
+    format!(
+        "{{\n  \"address\": \"{}\",\n  \"metadata\": {{ \"name\": \"multi_hub_swap\", \"version\": \"{}\", \"spec\": \"0.1.0\" }},\n  \"instructions\": {},\n  \"accounts\": {},\n  \"errors\": {},\n  \"types\": {}\n}}\n",
+        address,
+        env::var("CARGO_PKG_VERSION").unwrap_or_default(),
+        instructions,
+        account_list,
+        errors,
+        types,
+    )
+}
+
+/// Markdown instruction reference for client authors, generated from the same parse as the
+/// IDL so it cannot drift from the handlers
+fn render_markdown_reference(instructions: &[Instruction], errors: &[ErrorCode]) -> String {
+    let cell = |value: &str| value.replace('|', "\\|");
+    let mut out = String::from(
+        "# multi_hub_swap instruction reference\n\n\
+         Generated by build.rs from src/multi_hub_swap_complete.rs; do not edit. Each instruction's \
+         data is its 1-byte tag followed by its args, little-endian. Optional args are trailing and \
+         may be omitted. Remaining accounts are passed after the fixed ones.\n",
+    );
+    for instruction in instructions {
+        out.push_str(&format!("\n## {} {}\n\nHandler: `{}`\n\n", instruction.discriminator, instruction.name, instruction.handler));
+        if instruction.args.is_empty() {
+            out.push_str("Args: none\n\n");
+        } else {
+            let args: Vec<String> = instruction
+                .args
+                .iter()
+                .map(|arg| format!("`{}: {}{}`", arg.name, arg.ty, if arg.optional { "?" } else { "" }))
+                .collect();
+            out.push_str(&format!("Args: {}\n\n", args.join(", ")));
+        }
+        out.push_str("| # | Account | Flags | Description |\n|---|---|---|---|\n");
+        for (index, account) in instruction.accounts.iter().enumerate() {
+            let flags: Vec<&str> = [
+                (account.signer, "signer"),
+                (account.writable, "writable"),
+                (account.optional, "optional"),
+                (account.remaining, "remaining"),
+            ]
+            .iter()
+            .filter(|(set, _)| *set)
+            .map(|(_, flag)| *flag)
+            .collect();
+            out.push_str(&format!("| {} | `{}` | {} | {} |\n", index, account.name, flags.join(", "), cell(&account.description)));
+        }
+    }
+    out.push_str("\n## Errors\n\n| Code | Name | Message |\n|---|---|---|\n");
+    for error in errors {
+        out.push_str(&format!("| {} | {} | {} |\n", error.code, error.name, cell(&error.message)));
+    }
+    out
+}
+
+fn main() {
+    println!("cargo:rerun-if-changed={}", PROGRAM_SOURCE);
+    println!("cargo:rerun-if-changed={}", STATE_SOURCE);
+    println!("cargo:rerun-if-changed=build.rs");
+    println!("cargo:rerun-if-env-changed=MULTI_HUB_SWAP_PROGRAM_ID");
+
+    let source = fs::read_to_string(PROGRAM_SOURCE).expect("failed to read program source");
+    let codes = parse_error_codes(&source);
+
+    let mut table = String::from("pub const ERROR_CODES: &[(u32, &str, &str)] = &[\n");
+    let mut json = String::from("{\n  \"errors\": [\n");
+    for (i, error) in codes.iter().enumerate() {
+        table.push_str(&format!(
+            "    ({}, {:?}, {:?}),\n",
+            error.code, error.name, error.message
+        ));
+        json.push_str(&format!(
+            "    {{ \"code\": {}, \"name\": \"{}\", \"msg\": \"{}\" }}{}\n",
+            error.code,
+            json_escape(&error.name),
+            json_escape(&error.message),
+            if i + 1 < codes.len() { "," } else { "" }
+        ));
+    }
+    table.push_str("];\n");
+    json.push_str("  ]\n}\n");
+
+    let instructions = parse_instructions(&source);
+    verify_discriminators(&instructions);
+    let mut tags = String::from("pub const INSTRUCTION_TAGS: &[(u8, &str)] = &[\n");
+    for instruction in &instructions {
+        tags.push_str(&format!("    ({}, {:?}),\n", instruction.discriminator, instruction.name));
+    }
+    tags.push_str("];\n");
+    let state_source = fs::read_to_string(STATE_SOURCE).expect("failed to read state source");
+    let mut structs: Vec<Layout> =
+        parse_structs(&state_source).into_iter().filter(|layout| !layout.name.starts_with("Staking")).collect();
+    structs.extend(parse_structs(&source));
+    let idl = render_idl(&instructions, &parse_pdas(&source), &structs, &codes);
+    let anchor_idl = render_anchor_idl(&instructions, &structs, &codes);
+    let reference = render_markdown_reference(&instructions, &codes);
+
+    let out_dir = env::var("OUT_DIR").expect("OUT_DIR not set");
+    fs::write(Path::new(&out_dir).join("error_codes.rs"), &table).expect("failed to write error table");
+    fs::write(Path::new(&out_dir).join("error_codes.json"), &json).expect("failed to write error json");
+    fs::write(Path::new(&out_dir).join("instruction_tags.rs"), &tags).expect("failed to write instruction tags");
+    fs::write(Path::new(&out_dir).join("idl.json"), &idl).expect("failed to write idl json");
+    fs::write(Path::new(&out_dir).join("anchor_idl.json"), &anchor_idl).expect("failed to write anchor idl json");
+
+    let manifest_dir = env::var("CARGO_MANIFEST_DIR").expect("CARGO_MANIFEST_DIR not set");
+    let idl_dir = Path::new(&manifest_dir).join("..").join("target").join("idl");
+    if fs::create_dir_all(&idl_dir).is_ok() {
+        let _ = fs::write(idl_dir.join("multi_hub_swap_errors.json"), &json);
+        let _ = fs::write(idl_dir.join("multi_hub_swap.json"), &idl);
+        let _ = fs::write(idl_dir.join("multi_hub_swap_anchor.json"), &anchor_idl);
+        let _ = fs::write(idl_dir.join("multi_hub_swap.md"), &reference);
+    }
+}
@@ -0,0 +1,86 @@
+//! Current time for reward, cooldown and timelock logic
+//!
+//! Handlers read the time through [`unix_timestamp`] (or [`clock_unix_timestamp`] when the
+//! Clock sysvar is passed as an account) rather than from the clock directly. Built with the
+//! `test-clock` feature, a native program-test can pin that time with
+//! [`set_mock_unix_timestamp`] and step across 7-day and epoch boundaries without warping
+//! the bank clock in every test. The override is process-wide and never compiled on-chain.
+
+use solana_program::{clock::Clock, program_error::ProgramError, sysvar::Sysvar};
+
+#[cfg(feature = "test-clock")]
+use std::sync::atomic::{AtomicI64, Ordering};
+
+/// Stored while no mock time is set
+#[cfg(feature = "test-clock")]
+const NO_MOCK: i64 = i64::MIN;
+
+#[cfg(feature = "test-clock")]
+static MOCK_UNIX_TIMESTAMP: AtomicI64 = AtomicI64::new(NO_MOCK);
+
+/// Pin the time every handler reads until [`clear_mock_unix_timestamp`]
+#[cfg(feature = "test-clock")]
+pub fn set_mock_unix_timestamp(unix_timestamp: i64) {
+    MOCK_UNIX_TIMESTAMP.store(unix_timestamp, Ordering::SeqCst);
+}
+
+/// Move the pinned time forward by `seconds`, starting from `from` (usually the bank clock's
+/// time) when none is set. Returns the new time
+#[cfg(feature = "test-clock")]
+pub fn advance_mock_unix_timestamp(from: i64, seconds: i64) -> i64 {
+    let now = mock_unix_timestamp().unwrap_or(from).saturating_add(seconds);
+    set_mock_unix_timestamp(now);
+    now
+}
+
+/// Go back to the bank clock
+#[cfg(feature = "test-clock")]
+pub fn clear_mock_unix_timestamp() {
+    MOCK_UNIX_TIMESTAMP.store(NO_MOCK, Ordering::SeqCst);
+}
+
+/// The pinned time, if a test set one
+#[cfg(feature = "test-clock")]
+pub fn mock_unix_timestamp() -> Option<i64> {
+    Some(MOCK_UNIX_TIMESTAMP.load(Ordering::SeqCst)).filter(|now| *now != NO_MOCK)
+}
+
+/// Current Unix time from the Clock sysvar, or the pinned time in `test-clock` builds
+pub fn unix_timestamp() -> Result<i64, ProgramError> {
+    #[cfg(feature = "test-clock")]
+    if let Some(now) = mock_unix_timestamp() {
+        return Ok(now);
+    }
+    Ok(Clock::get()?.unix_timestamp)
+}
+
+/// Unix time of a Clock read from its sysvar account, or the pinned time in `test-clock` builds
+pub fn clock_unix_timestamp(clock: &Clock) -> i64 {
+    #[cfg(feature = "test-clock")]
+    if let Some(now) = mock_unix_timestamp() {
+        return now;
+    }
+    clock.unix_timestamp
+}
+
+#[cfg(all(test, feature = "test-clock"))]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn mock_time_overrides_the_clock_until_cleared() {
+        let clock = Clock { unix_timestamp: 1_000, ..Clock::default() };
+        assert_eq!(clock_unix_timestamp(&clock), 1_000);
+
+        set_mock_unix_timestamp(5_000);
+        assert_eq!(clock_unix_timestamp(&clock), 5_000);
+        assert_eq!(unix_timestamp().unwrap(), 5_000);
+        assert_eq!(advance_mock_unix_timestamp(0, 7 * 86_400), 5_000 + 7 * 86_400);
+
+        clear_mock_unix_timestamp();
+        assert_eq!(mock_unix_timestamp(), None);
+        assert_eq!(clock_unix_timestamp(&clock), 1_000);
+        assert_eq!(advance_mock_unix_timestamp(1_000, 60), 1_060);
+        clear_mock_unix_timestamp();
+    }
+}
@@ -0,0 +1,79 @@
+//! Known mints and program ids, and the fee limits no admin instruction can exceed
+//!
+//! Devnet values are compiled in by default; build with `--features mainnet` for mainnet.
+//! These are only defaults: both processors keep the YOT and YOS mints in their program
+//! state, which Initialize fills from the instruction and falls back to the defaults here
+//! when a mint is passed as the all-zero pubkey.
+
+use solana_program::{pubkey, pubkey::Pubkey};
+
+/// Wrapped SOL mint
+pub const SOL_MINT: Pubkey = spl_token::native_mint::ID;
+
+/// Associated Token Account program
+pub const ASSOCIATED_TOKEN_PROGRAM_ID: Pubkey = pubkey!("ATokenGPvbdGVxr1b2hvZbsiqW5xWH25efTNsLJA8knL");
+
+/// Metaplex Token Metadata program, which wallets read LP token names from
+pub const TOKEN_METADATA_PROGRAM_ID: Pubkey = pubkey!("metaqbxxUerdq28cj1RbAWkYQm3ybzjb6a8bt518x1s");
+
+/// SPL Memo program (v2), which swaps call to tag their transaction with a caller's memo
+pub const MEMO_PROGRAM_ID: Pubkey = pubkey!("MemoSq4gqABAXKb96qnH8TysNcWxMyWCqXgDLGmfcHr");
+
+/// The earlier multi-hub swap deployment whose contributions ImportContribution moves here.
+/// Only accounts this program owns are accepted as legacy contributions
+pub const LEGACY_PROGRAM_ID: Pubkey = pubkey!("SMddVoXz2hF9jjecS5A1gZLG8TJHo34MJZuexZ8kVjE");
+
+/// Devnet YOT mint
+#[cfg(not(feature = "mainnet"))]
+pub const YOT_MINT: Pubkey = pubkey!("9KxQHJcBxp29AjGTAqF3LCFzodSpkuv986wsSEwQi6Cw");
+
+/// Devnet YOS mint
+#[cfg(not(feature = "mainnet"))]
+pub const YOS_MINT: Pubkey = pubkey!("2SWCnck3vLAVKaLkAjVtNnsVJVGYmGzyNVnte48SQRop");
+
+/// YOT is not issued on mainnet yet, so Initialize must pass the mint
+#[cfg(feature = "mainnet")]
+pub const YOT_MINT: Pubkey = Pubkey::new_from_array([0; 32]);
+
+/// YOS is not issued on mainnet yet, so Initialize must pass the mint
+#[cfg(feature = "mainnet")]
+pub const YOS_MINT: Pubkey = Pubkey::new_from_array([0; 32]);
+
+/// `mint`, or `default` when `mint` is the all-zero pubkey. `None` when neither is set.
+pub fn mint_or_default(mint: Pubkey, default: Pubkey) -> Option<Pubkey> {
+    [mint, default].into_iter().find(|key| *key != Pubkey::default())
+}
+
+/// Highest swap fee any instruction may set, in percent
+pub const MAX_SWAP_FEE_RATE: u64 = 1;
+
+/// Highest liquidity contribution cut any instruction may set, in percent
+pub const MAX_LP_CONTRIBUTION_RATE: u64 = 25;
+
+/// Highest SOL commission on SOL-in swaps any instruction may set, in basis points
+pub const MAX_SOL_FEE_BPS: u64 = 100;
+
+/// Highest share of a pool's swap fee the protocol fee switch may divert, in basis points of the fee
+pub const MAX_PROTOCOL_FEE_BPS: u64 = 5_000;
+
+/// Highest share of each pool swap the insurance fund may take out of the swap fee, in basis
+/// points of the swap input
+pub const MAX_INSURANCE_FEE_BPS: u64 = 100;
+
+/// Highest share of the value a permissionless crank moves that may be paid to its caller,
+/// in basis points
+pub const MAX_CRANK_FEE_BPS: u64 = 50;
+
+/// Highest dust threshold SetDustPolicy may set, in YOT base units (0.001 YOT at 9 decimals)
+/// A dust close refunds the principal, but the threshold still only covers positions too
+/// small to be worth a withdrawal
+pub const MAX_DUST_THRESHOLD: u64 = 1_000_000;
+
+/// Longest commit-reveal window any instruction may set, in slots (about a minute)
+pub const MAX_COMMIT_REVEAL_WINDOW_SLOTS: u64 = 150;
+
+// The hard limits must sit inside the looser runtime checks
+const _: () = assert!(
+    MAX_SWAP_FEE_RATE <= 100 && MAX_LP_CONTRIBUTION_RATE <= 100 && MAX_SOL_FEE_BPS <= 10_000 && MAX_PROTOCOL_FEE_BPS <= 10_000
+        && MAX_INSURANCE_FEE_BPS <= 10_000 && MAX_CRANK_FEE_BPS <= 10_000
+);
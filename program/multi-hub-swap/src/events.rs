@@ -0,0 +1,69 @@
+//! Structured events the multi-hub swap program logs for indexers
+//!
+//! Events are `sol_log_data` records whose first field names the event. Most carry raw
+//! little-endian fields, documented by an `// Event:` comment where they are emitted.
+//! Contribution receipts are the exception: their payload is memo-style UTF-8 JSON, so an
+//! indexer that already reads SPL Memo data can consume them without a byte layout.
+//!
+//! # Contribution receipt
+//!
+//! Logged on every change to a liquidity contribution as
+//! `sol_log_data(["contribution_receipt", <memo>])`. `<memo>` is one JSON object:
+//!
+//! | Key | Type | Meaning |
+//! |-----|------|---------|
+//! | `v` | number | Schema version, currently `1` |
+//! | `user` | string | Contribution owner, base58 |
+//! | `source` | string | Instruction that made the change, by its IDL name (`contribute`, `buy_and_distribute`, ...) |
+//! | `amount` | string | Signed change in the contributed amount, in decimal (`-` for withdrawals) |
+//! | `total` | string | Contributed amount after the change, in decimal |
+//!
+//! Amounts are strings because u64 values lose precision as JSON numbers in JavaScript.
+//! A transfer to another wallet logs two receipts: the old owner's total drops to 0 and
+//! the new owner's rises by the same amount. New keys may be added within a version.
+
+use solana_program::{log::sol_log_data, pubkey::Pubkey};
+
+/// Name of the contribution receipt event
+pub const CONTRIBUTION_RECEIPT: &[u8] = b"contribution_receipt";
+
+/// Schema version written in every contribution receipt
+pub const CONTRIBUTION_RECEIPT_VERSION: u8 = 1;
+
+/// Memo payload of a contribution receipt, as documented above
+pub fn contribution_receipt_memo(user: &Pubkey, source: &str, previous_total: u64, total: u64) -> String {
+    let change = total.abs_diff(previous_total);
+    let amount = if total >= previous_total {
+        change.to_string()
+    } else {
+        format!("-{}", change)
+    };
+    format!(
+        r#"{{"v":{},"user":"{}","source":"{}","amount":"{}","total":"{}"}}"#,
+        CONTRIBUTION_RECEIPT_VERSION, user, source, amount, total,
+    )
+}
+
+/// Log a contribution receipt for a contribution that went from `previous_total` to `total`
+pub fn emit_contribution_receipt(user: &Pubkey, source: &str, previous_total: u64, total: u64) {
+    let memo = contribution_receipt_memo(user, source, previous_total, total);
+    sol_log_data(&[CONTRIBUTION_RECEIPT, memo.as_bytes()]);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn receipt_memo_is_signed_decimal_json() {
+        let user = Pubkey::new_from_array([1; 32]);
+        assert_eq!(
+            contribution_receipt_memo(&user, "withdraw_liquidity", u64::MAX, 0),
+            format!(r#"{{"v":1,"user":"{}","source":"withdraw_liquidity","amount":"-{}","total":"0"}}"#, user, u64::MAX),
+        );
+        assert_eq!(
+            contribution_receipt_memo(&user, "contribute", 5, 12),
+            format!(r#"{{"v":1,"user":"{}","source":"contribute","amount":"7","total":"12"}}"#, user),
+        );
+    }
+}
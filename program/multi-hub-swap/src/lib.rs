@@ -0,0 +1,41 @@
+//! YOT on-chain program
+//!
+//! Exactly one processor is compiled in, chosen by feature:
+//! - `processor-multi-hub-swap` (default): the multi-hub swap program
+//! - `processor-staking`: the YOT staking program
+//!
+//! Build the staking program with
+//! `cargo build-sbf --no-default-features --features processor-staking`.
+//! Superseded processors live in the `multi-hub-swap-archive` crate and are never deployed.
+
+#[cfg(all(feature = "processor-multi-hub-swap", feature = "processor-staking"))]
+compile_error!("Select exactly one processor feature: processor-multi-hub-swap or processor-staking");
+
+#[cfg(not(any(feature = "processor-multi-hub-swap", feature = "processor-staking")))]
+compile_error!("No processor selected: enable processor-multi-hub-swap or processor-staking");
+
+#[cfg(all(feature = "metrics", feature = "mainnet"))]
+compile_error!("The metrics feature is for devnet builds and cannot be combined with mainnet");
+
+#[cfg(all(feature = "test-clock", any(feature = "mainnet", target_os = "solana")))]
+compile_error!("The test-clock feature is for native program-test builds only");
+
+pub mod clock;
+pub mod constants;
+pub mod events;
+pub mod state;
+
+#[cfg(test)]
+mod serialization_tests;
+
+#[cfg(feature = "processor-multi-hub-swap")]
+pub mod multi_hub_swap_complete;
+#[cfg(feature = "processor-multi-hub-swap")]
+pub mod token_accounts;
+#[cfg(feature = "processor-multi-hub-swap")]
+pub use multi_hub_swap_complete as processor;
+
+#[cfg(feature = "processor-staking")]
+pub mod staking;
+#[cfg(feature = "processor-staking")]
+pub use staking as processor;
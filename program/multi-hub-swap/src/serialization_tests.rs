@@ -0,0 +1,464 @@
+//! Byte-layout tests for every stored account type
+//!
+//! Each type is unpacked from a patterned buffer and packed back, which must reproduce the
+//! buffer exactly. Versioned types are also read from every earlier layout, whose bytes must
+//! come back unchanged at the start of the current one. A buffer one byte short of the oldest
+//! layout must fail both ways, and a longer buffer must round-trip without its tail being
+//! touched. A layout change that moves or resizes a field fails here first.
+
+use crate::state::{ProgramState, StakingProgramState};
+use solana_program::program_error::ProgramError;
+
+/// Deterministic non-zero bytes, distinct at every offset a field can start at
+fn patterned(len: usize) -> Vec<u8> {
+    (0..len).map(|i| (i as u8).wrapping_mul(29).wrapping_add(11)).collect()
+}
+
+const TAIL: u8 = 0xEE;
+const TAIL_LEN: usize = 16;
+
+/// Round-trip `layouts` (oldest first, current last) of one type
+/// `fixup` makes the patterned bytes valid for the type: counts in range, unused slots zero
+fn assert_layouts<T>(
+    layouts: &[usize],
+    unpack: fn(&[u8]) -> Result<T, ProgramError>,
+    pack: fn(&T, &mut [u8]) -> Result<(), ProgramError>,
+    fixup: fn(&mut [u8]),
+) {
+    let (&len, legacy_layouts) = layouts.split_last().unwrap();
+    let mut bytes = patterned(len);
+    fixup(&mut bytes);
+
+    // Current layout, exact buffer
+    let value = unpack(&bytes).unwrap();
+    let mut packed = vec![0u8; len];
+    pack(&value, &mut packed).unwrap();
+    assert_eq!(packed, bytes, "current layout of {} bytes changed", len);
+
+    // Earlier layouts keep their bytes at the start of the current one
+    for &legacy in legacy_layouts {
+        let value = unpack(&bytes[..legacy]).unwrap();
+        let mut packed = vec![0u8; len];
+        pack(&value, &mut packed).unwrap();
+        assert_eq!(&packed[..legacy], &bytes[..legacy], "layout of {} bytes changed", legacy);
+    }
+
+    // Truncated below the oldest layout
+    let short = layouts[0].checked_sub(1).unwrap();
+    assert!(unpack(&bytes[..short]).is_err(), "{} bytes unpacked", short);
+    assert!(pack(&value, &mut vec![0u8; short]).is_err(), "packed into {} bytes", short);
+
+    // Oversized: the tail is ignored on read and left alone on write
+    let mut oversized = bytes.clone();
+    oversized.extend_from_slice(&[TAIL; TAIL_LEN]);
+    let value = unpack(&oversized).unwrap();
+    let mut packed = vec![TAIL; oversized.len()];
+    pack(&value, &mut packed).unwrap();
+    assert_eq!(packed, oversized, "oversized buffer of {} bytes changed", oversized.len());
+}
+
+fn no_fixup(_: &mut [u8]) {}
+
+fn layouts_with(legacy: &[usize], current: usize) -> Vec<usize> {
+    let mut layouts = legacy.to_vec();
+    layouts.push(current);
+    layouts
+}
+
+#[test]
+fn program_state_layouts() {
+    assert_layouts(
+        &layouts_with(&ProgramState::LEGACY_LENS, ProgramState::LEN),
+        ProgramState::unpack,
+        ProgramState::pack,
+        no_fixup,
+    );
+}
+
+#[test]
+fn staking_program_state_layouts() {
+    // Versioned layout: protocol version, then the unversioned fields
+    let mut bytes = patterned(StakingProgramState::LEN);
+    bytes[0] = StakingProgramState::PROTOCOL_VERSION;
+    let state = StakingProgramState::unpack(&bytes).unwrap();
+    let mut packed = vec![0u8; StakingProgramState::LEN];
+    state.pack(&mut packed).unwrap();
+    assert_eq!(packed, bytes);
+
+    // First-deployment layout has no version byte and is written back as it was
+    let unversioned = &bytes[1..];
+    let state = StakingProgramState::unpack(unversioned).unwrap();
+    assert_eq!(state.protocol_version, 0);
+    let mut packed = vec![0u8; StakingProgramState::LEN_V0];
+    state.pack(&mut packed).unwrap();
+    assert_eq!(packed, unversioned);
+
+    // Below the oldest layout
+    assert!(StakingProgramState::unpack(&bytes[..StakingProgramState::LEN_V0 - 1]).is_err());
+    assert!(state.pack(&mut [0u8; StakingProgramState::LEN_V0 - 1]).is_err());
+
+    // Unknown protocol version
+    bytes[0] = StakingProgramState::PROTOCOL_VERSION.checked_add(1).unwrap();
+    assert!(StakingProgramState::unpack(&bytes).is_err());
+}
+
+#[cfg(feature = "processor-multi-hub-swap")]
+mod multi_hub_swap {
+    use super::*;
+    use crate::multi_hub_swap_complete::*;
+    use solana_program::pubkey::Pubkey;
+
+    /// Pack-only types: the fields must land in declaration order and the buffer must be long enough
+    fn assert_pack_only<T>(value: &T, expected: &[u8], pack: fn(&T, &mut [u8]) -> Result<(), ProgramError>) {
+        let mut packed = vec![0u8; expected.len()];
+        pack(value, &mut packed).unwrap();
+        assert_eq!(packed, expected);
+
+        assert!(pack(value, &mut vec![0u8; expected.len().checked_sub(1).unwrap()]).is_err());
+
+        let mut packed = vec![TAIL; expected.len()];
+        packed.extend_from_slice(&[TAIL; TAIL_LEN]);
+        pack(value, &mut packed).unwrap();
+        assert_eq!(&packed[..expected.len()], expected);
+        assert!(packed[expected.len()..].iter().all(|byte| *byte == TAIL));
+    }
+
+    #[test]
+    fn liquidity_contribution_layouts() {
+        assert_layouts(
+            &layouts_with(&LiquidityContribution::LEGACY_LENS, LiquidityContribution::LEN),
+            LiquidityContribution::unpack,
+            LiquidityContribution::pack,
+            no_fixup,
+        );
+    }
+
+    #[test]
+    fn global_contribution_state_layouts() {
+        assert_layouts(
+            &[GlobalContributionState::LEN_V1, GlobalContributionState::LEN],
+            GlobalContributionState::unpack,
+            GlobalContributionState::pack,
+            no_fixup,
+        );
+    }
+
+    #[test]
+    fn emission_schedule_layout() {
+        assert_layouts(&[EmissionSchedule::LEN], EmissionSchedule::unpack, EmissionSchedule::pack, |bytes| {
+            bytes[0] = EmissionSchedule::MAX_STEPS as u8;
+        });
+    }
+
+    #[test]
+    fn user_history_layouts() {
+        assert_layouts(
+            &[
+                UserHistory::LEN_V1, UserHistory::LEN_V2, UserHistory::LEN_V3,
+                UserHistory::LEN_V4, UserHistory::LEN_V5, UserHistory::LEN_V6,
+                UserHistory::LEN,
+            ],
+            UserHistory::unpack,
+            UserHistory::pack,
+            no_fixup,
+        );
+    }
+
+    #[test]
+    fn allowlist_page_layout() {
+        assert_layouts(&[AllowlistPage::LEN], AllowlistPage::unpack, AllowlistPage::pack, |bytes| {
+            bytes[2..4].copy_from_slice(&(AllowlistPage::MAX_ENTRIES as u16).to_le_bytes());
+        });
+    }
+
+    #[test]
+    fn price_observation_layout() {
+        assert_layouts(&[PriceObservation::LEN], PriceObservation::unpack, PriceObservation::pack, no_fixup);
+    }
+
+    #[test]
+    fn liquidity_pool_layouts() {
+        assert_layouts(
+            &[LiquidityPool::LEN_V1, LiquidityPool::LEN_V2, LiquidityPool::LEN_V3, LiquidityPool::LEN],
+            LiquidityPool::unpack,
+            LiquidityPool::pack,
+            no_fixup,
+        );
+    }
+
+    #[test]
+    fn pool_registry_layouts() {
+        assert_layouts(
+            &[PoolRegistry::LEN_V1, PoolRegistry::LEN],
+            PoolRegistry::unpack,
+            PoolRegistry::pack,
+            |bytes| {
+                bytes[0] = PoolRegistry::MAX_POOLS as u8;
+                // One pause flag per slot, alternating
+                for (i, flag) in bytes[PoolRegistry::LEN_V1..].iter_mut().enumerate() {
+                    *flag = (i % 2) as u8;
+                }
+            },
+        );
+    }
+
+    #[test]
+    fn mint_decimals_registry_layout() {
+        assert_layouts(&[MintDecimalsRegistry::LEN], MintDecimalsRegistry::unpack, MintDecimalsRegistry::pack, |bytes| {
+            bytes[0] = MintDecimalsRegistry::MAX_ENTRIES as u8;
+        });
+    }
+
+    #[test]
+    fn program_token_account_registry_layout() {
+        assert_layouts(
+            &[ProgramTokenAccountRegistry::LEN],
+            ProgramTokenAccountRegistry::unpack,
+            ProgramTokenAccountRegistry::pack,
+            |bytes| bytes[0] = ProgramTokenAccountRegistry::MAX_ACCOUNTS as u8,
+        );
+    }
+
+    #[test]
+    fn sol_fee_vault_layout() {
+        assert_layouts(&[SolFeeVault::LEN], SolFeeVault::unpack, SolFeeVault::pack, no_fixup);
+    }
+
+    #[test]
+    fn central_wallet_caps_layout() {
+        assert_layouts(&[CentralWalletCaps::LEN], CentralWalletCaps::unpack, CentralWalletCaps::pack, |bytes| {
+            bytes[0] = CentralWalletCaps::MAX_CAPS as u8;
+        });
+    }
+
+    #[test]
+    fn protocol_totals_layout() {
+        assert_layouts(&[ProtocolTotals::LEN], ProtocolTotals::unpack, ProtocolTotals::pack, |bytes| {
+            bytes[ProtocolTotals::HEADER_LEN - 1] = ProtocolTotals::MAX_FEE_MINTS as u8;
+        });
+    }
+
+    #[test]
+    fn central_liquidity_state_layout() {
+        assert_layouts(&[CentralLiquidityState::LEN], CentralLiquidityState::unpack, CentralLiquidityState::pack, no_fixup);
+    }
+
+    #[test]
+    fn rebate_layouts() {
+        assert_layouts(&[RebateEpoch::LEN], RebateEpoch::unpack, RebateEpoch::pack, no_fixup);
+        assert_layouts(&[RebateClaim::LEN], RebateClaim::unpack, RebateClaim::pack, no_fixup);
+    }
+
+    #[test]
+    fn referral_board_layouts() {
+        assert_layouts(&[ReferralBoardPage::LEN], ReferralBoardPage::unpack, ReferralBoardPage::pack, |bytes| {
+            bytes[10..12].copy_from_slice(&(ReferralBoardPage::MAX_ENTRIES as u16).to_le_bytes());
+        });
+        assert_layouts(&[ReferralEpochClose::LEN], ReferralEpochClose::unpack, ReferralEpochClose::pack, no_fixup);
+    }
+
+    #[test]
+    fn yos_lock_and_xyos_vault_layouts() {
+        assert_layouts(&[YosLock::LEN], YosLock::unpack, YosLock::pack, no_fixup);
+        assert_layouts(&[XyosVault::LEN], XyosVault::unpack, XyosVault::pack, no_fixup);
+    }
+
+    #[test]
+    fn yot_migration_layouts() {
+        assert_layouts(&[YotMigration::LEN], YotMigration::unpack, YotMigration::pack, no_fixup);
+        assert_layouts(&[YotMigrationReceipt::LEN], YotMigrationReceipt::unpack, YotMigrationReceipt::pack, no_fixup);
+    }
+
+    #[test]
+    fn order_layouts() {
+        assert_layouts(&[SwapDelegate::LEN], SwapDelegate::unpack, SwapDelegate::pack, no_fixup);
+        assert_layouts(&[SwapCommitment::LEN], SwapCommitment::unpack, SwapCommitment::pack, no_fixup);
+        assert_layouts(&[RfqFill::LEN], RfqFill::unpack, RfqFill::pack, no_fixup);
+        assert_layouts(&[LimitOrder::LEN], LimitOrder::unpack, LimitOrder::pack, no_fixup);
+        assert_layouts(&[DcaSchedule::LEN], DcaSchedule::unpack, DcaSchedule::pack, no_fixup);
+    }
+
+    #[test]
+    fn airdrop_and_insurance_layouts() {
+        assert_layouts(&[AirdropSnapshot::LEN], AirdropSnapshot::unpack, AirdropSnapshot::pack, no_fixup);
+        assert_layouts(&[InsurancePayout::LEN], InsurancePayout::unpack, InsurancePayout::pack, no_fixup);
+    }
+
+    #[test]
+    fn payout_route_layout() {
+        assert_layouts(&[PayoutRoute::LEN], PayoutRoute::unpack, PayoutRoute::pack, |bytes| {
+            // Every hop slot in use
+            bytes[32] = (PayoutRoute::LEN - 32 - 1) as u8;
+        });
+    }
+
+    #[test]
+    fn cashback_adjustment_layout() {
+        assert_layouts(&[CashbackAdjustment::LEN], CashbackAdjustment::unpack, CashbackAdjustment::pack, no_fixup);
+    }
+
+    #[test]
+    fn mining_layouts() {
+        assert_layouts(&[MiningCampaign::LEN], MiningCampaign::unpack, MiningCampaign::pack, no_fixup);
+        assert_layouts(&[MiningStake::LEN], MiningStake::unpack, MiningStake::pack, no_fixup);
+    }
+
+    #[test]
+    fn treasury_distribution_layout() {
+        assert_layouts(&[TreasuryDistribution::LEN], TreasuryDistribution::unpack, TreasuryDistribution::pack, |bytes| {
+            bytes[8 + 32 + 32 + 8 + 8] = TreasuryDistribution::MAX_PAYMENTS as u8;
+        });
+    }
+
+    #[test]
+    fn bridge_intent_layout() {
+        assert_layouts(&[BridgeIntent::LEN], BridgeIntent::unpack, BridgeIntent::pack, |bytes| {
+            bytes[32 + 32 + 8 + 8] = BridgeIntent::MAX_RECIPIENT_LEN as u8;
+        });
+    }
+
+    #[test]
+    fn promotion_layout() {
+        assert_layouts(&[Promotion::LEN], Promotion::unpack, Promotion::pack, no_fixup);
+    }
+
+    #[test]
+    fn user_registry_layouts() {
+        assert_layouts(&[UserRegistry::LEN], UserRegistry::unpack, UserRegistry::pack, no_fixup);
+        assert_layouts(&[UserRegistryEntry::LEN], UserRegistryEntry::unpack, UserRegistryEntry::pack, no_fixup);
+    }
+
+    #[test]
+    fn user_registry_page_layout() {
+        // Pack writes the header only and leaves the entries in place
+        let bytes = patterned(UserRegistryPage::LEN);
+        let page = UserRegistryPage::unpack(&bytes).unwrap();
+        let mut packed = vec![0u8; UserRegistryPage::LEN];
+        page.pack(&mut packed).unwrap();
+        assert_eq!(&packed[..UserRegistryPage::HEADER_LEN], &bytes[..UserRegistryPage::HEADER_LEN]);
+        assert!(packed[UserRegistryPage::HEADER_LEN..].iter().all(|byte| *byte == 0));
+
+        // Entries sit back to back after the header
+        let user = Pubkey::new_from_array([7; 32]);
+        let last = UserRegistryPage::ENTRIES_PER_PAGE - 1;
+        UserRegistryPage::set_entry(&mut packed, last, &user, UserRegistryPage::FLAG_CONTRIBUTED).unwrap();
+        assert_eq!(UserRegistryPage::entry(&packed, last).unwrap(), (user, UserRegistryPage::FLAG_CONTRIBUTED));
+        let entry_start = UserRegistryPage::LEN - UserRegistryPage::ENTRY_LEN;
+        assert_eq!(&packed[entry_start..entry_start + 32], user.as_ref());
+        assert_eq!(&packed[entry_start + 32..], &UserRegistryPage::FLAG_CONTRIBUTED.to_le_bytes());
+        assert!(UserRegistryPage::entry(&packed, UserRegistryPage::ENTRIES_PER_PAGE).is_err());
+
+        assert!(UserRegistryPage::unpack(&bytes[..UserRegistryPage::LEN - 1]).is_err());
+        assert!(page.pack(&mut vec![0u8; UserRegistryPage::LEN - 1]).is_err());
+
+        let mut oversized = vec![TAIL; UserRegistryPage::LEN + TAIL_LEN];
+        page.pack(&mut oversized).unwrap();
+        assert!(oversized[UserRegistryPage::LEN..].iter().all(|byte| *byte == TAIL));
+    }
+
+    #[test]
+    fn claim_record_layouts() {
+        let user = Pubkey::new_from_array([7; 32]);
+        let legacy_program_id = Pubkey::new_from_array([9; 32]);
+        let tail = [11u64.to_le_bytes(), (-12i64).to_le_bytes()].concat();
+
+        assert_pack_only(
+            &ImportRecord { user, legacy_program_id, contributed_amount: 11, imported_at: -12 },
+            &[user.as_ref(), legacy_program_id.as_ref(), &tail].concat(),
+            ImportRecord::pack,
+        );
+        assert_pack_only(
+            &AirdropClaim { user, amount: 11, claimed_at: -12 },
+            &[user.as_ref(), &tail].concat(),
+            AirdropClaim::pack,
+        );
+        assert_pack_only(
+            &InsuranceClaim { user, amount: 11, claimed_at: -12 },
+            &[user.as_ref(), &tail].concat(),
+            InsuranceClaim::pack,
+        );
+    }
+
+    #[test]
+    fn return_data_layouts() {
+        let swap_result = SwapResult {
+            amount_in: 1,
+            amount_out: 2,
+            intermediate_amount: 3,
+            liquidity_contribution: 4,
+            yos_cashback: 5,
+            amount_unfilled: 6,
+        };
+        let expected: Vec<u8> = (1..=6u64).flat_map(u64::to_le_bytes).collect();
+        assert_eq!(swap_result.pack().to_vec(), expected);
+
+        let user = Pubkey::new_from_array([7; 32]);
+        let position = UserPosition {
+            user,
+            contributed_amount: 1,
+            pending_weekly_reward: 2,
+            claimable_reward: 3,
+            next_claim_time: 4,
+            pending_cashback_yos: 5,
+            pending_cashback_yot: 6,
+            staked_lp: 7,
+            as_of: 8,
+            accrued_cashback: 9,
+        };
+        let expected = [user.as_ref(), &(1..=9u64).flat_map(u64::to_le_bytes).collect::<Vec<u8>>()].concat();
+        assert_eq!(position.pack().to_vec(), expected);
+    }
+}
+
+#[cfg(feature = "processor-staking")]
+mod staking {
+    use super::*;
+    use crate::staking::{StakingAccount, StakingInstruction};
+    use borsh::{BorshDeserialize, BorshSerialize};
+
+    #[test]
+    fn staking_account_borsh_layout() {
+        // Accounts are created with size_of::<StakingAccount>() bytes and read with
+        // try_from_slice, so the Borsh encoding must fill that exactly
+        let len = std::mem::size_of::<StakingAccount>();
+        assert_eq!(len, 32 + 8 + 8 + 8 + 8);
+
+        let bytes = patterned(len);
+        let account = StakingAccount::try_from_slice(&bytes).unwrap();
+        assert_eq!(account.owner.as_ref(), &bytes[..32]);
+        assert_eq!(account.total_harvested.to_le_bytes(), bytes[56..]);
+        assert_eq!(borsh::to_vec(&account).unwrap(), bytes);
+
+        assert!(StakingAccount::try_from_slice(&bytes[..len - 1]).is_err());
+        assert!(account.serialize(&mut &mut vec![0u8; len - 1][..]).is_err());
+
+        // Trailing bytes are rejected on read and left alone on write
+        let mut oversized = bytes.clone();
+        oversized.extend_from_slice(&[TAIL; TAIL_LEN]);
+        assert!(StakingAccount::try_from_slice(&oversized).is_err());
+        let mut packed = vec![TAIL; len + TAIL_LEN];
+        account.serialize(&mut &mut packed[..]).unwrap();
+        assert_eq!(packed, oversized);
+    }
+
+    #[test]
+    fn staking_instruction_borsh_layout() {
+        let fields = patterned(32 + 32 + 8 + 8);
+        let encodings = [
+            [&[0u8][..], &fields].concat(),
+            [&[1u8][..], &fields[..8]].concat(),
+            [&[2u8][..], &fields[..8]].concat(),
+            vec![3u8],
+            [&[4u8][..], &fields[..16]].concat(),
+        ];
+        for bytes in encodings {
+            let instruction = StakingInstruction::try_from_slice(&bytes).unwrap();
+            assert_eq!(borsh::to_vec(&instruction).unwrap(), bytes);
+            if bytes.len() > 1 {
+                assert!(StakingInstruction::try_from_slice(&bytes[..bytes.len() - 1]).is_err());
+            }
+            assert!(StakingInstruction::try_from_slice(&[bytes.as_slice(), &[TAIL]].concat()).is_err());
+        }
+        assert!(StakingInstruction::try_from_slice(&[5]).is_err());
+    }
+}
@@ -12,10 +12,13 @@ use solana_program::{
     system_instruction,
     sysvar::{clock::Clock, Sysvar},
 };
-use spl_token::state::{Account as TokenAccount, Mint};
-use std::convert::TryInto;
+use spl_token::state::Account as TokenAccount;
+use crate::clock::clock_unix_timestamp;
+use crate::constants::{mint_or_default, YOS_MINT, YOT_MINT};
+use crate::state::StakingProgramState;
 
 // Declare program entrypoint
+#[cfg(not(feature = "no-entrypoint"))]
 entrypoint!(process_instruction);
 
 // CRITICAL FIX: Add display normalization factor to fix wallet display issue
@@ -23,10 +26,6 @@ entrypoint!(process_instruction);
 // This factor will be applied during token transfers to normalize the display
 const YOS_DISPLAY_NORMALIZATION_FACTOR: u64 = 9_200_000;
 
-// CRITICAL FIX: Add version number to track protocol updates
-// This will allow us to handle migrations in the future if needed
-const PROTOCOL_VERSION: u8 = 1;
-
 // Instruction types
 #[derive(BorshSerialize, BorshDeserialize, Debug)]
 pub enum StakingInstruction {
@@ -65,23 +64,10 @@ pub enum StakingInstruction {
         stake_rate_per_second: u64,
         harvest_threshold: u64,
     },
-}
-
-// Program state stored in a PDA
-#[derive(BorshSerialize, BorshDeserialize, Debug)]
-pub struct ProgramState {
-    // CRITICAL FIX: Add protocol version number to support migrations
-    pub protocol_version: u8,
-    // Admin public key that can update parameters
-    pub admin: Pubkey,
-    // YOT token mint address
-    pub yot_mint: Pubkey,
-    // YOS token mint address
-    pub yos_mint: Pubkey,
-    // Staking rate in basis points (1/100 of 1%)
-    pub stake_rate_per_second: u64,
-    // Minimum YOS for harvest in basis points
-    pub harvest_threshold: u64,
+    
+    // Rewrite an unversioned program state into the current layout
+    // Requires the payer's signature; anyone may pay
+    MigrateState,
 }
 
 // Staking account data for each user
@@ -148,7 +134,57 @@ pub fn process_instruction(
                 harvest_threshold,
             )
         }
+        
+        StakingInstruction::MigrateState => {
+            process_migrate_state(program_id, accounts)
+        }
+    }
+}
+
+// Rewrite an unversioned program state into the current layout, growing the account with
+// rent from the payer. Until then the unversioned layout is still read as before
+fn process_migrate_state(program_id: &Pubkey, accounts: &[AccountInfo]) -> ProgramResult {
+    let account_info_iter = &mut accounts.iter();
+    
+    // Get accounts
+    let payer = next_account_info(account_info_iter)?;
+    let program_state_account = next_account_info(account_info_iter)?;
+    let system_program = next_account_info(account_info_iter)?;
+    
+    if !payer.is_signer {
+        return Err(ProgramError::MissingRequiredSignature);
+    }
+    if *system_program.key != solana_program::system_program::id() {
+        return Err(ProgramError::IncorrectProgramId);
+    }
+    let (pda, _) = Pubkey::find_program_address(&[b"program_state"], program_id);
+    if pda != *program_state_account.key || program_state_account.owner != program_id {
+        return Err(ProgramError::InvalidAccountData);
+    }
+    if program_state_account.data_len() != StakingProgramState::LEN_V0 {
+        msg!("Program state is already in the current layout");
+        return Err(ProgramError::InvalidAccountData);
+    }
+    
+    let mut program_state = StakingProgramState::unpack(&program_state_account.data.borrow())?;
+    program_state.protocol_version = StakingProgramState::PROTOCOL_VERSION;
+    
+    // Top up rent for the larger layout before growing the account
+    let rent = Rent::get()?;
+    let shortfall = rent
+        .minimum_balance(StakingProgramState::LEN)
+        .saturating_sub(program_state_account.lamports());
+    if shortfall > 0 {
+        invoke(
+            &system_instruction::transfer(payer.key, program_state_account.key, shortfall),
+            &[payer.clone(), program_state_account.clone(), system_program.clone()],
+        )?;
     }
+    program_state_account.realloc(StakingProgramState::LEN, true)?;
+    program_state.pack(&mut program_state_account.try_borrow_mut_data()?[..])?;
+    
+    msg!("Upgraded staking program state to protocol version {}", StakingProgramState::PROTOCOL_VERSION);
+    Ok(())
 }
 
 // Initialize the staking program
@@ -172,6 +208,10 @@ fn process_initialize(
         return Err(ProgramError::MissingRequiredSignature);
     }
     
+    // An all-zero mint selects the built-in default for this cluster
+    let yot_mint = mint_or_default(yot_mint, YOT_MINT).ok_or(ProgramError::InvalidInstructionData)?;
+    let yos_mint = mint_or_default(yos_mint, YOS_MINT).ok_or(ProgramError::InvalidInstructionData)?;
+    
     // Calculate PDA for program state account
     let (pda, bump_seed) = Pubkey::find_program_address(&[b"program_state"], program_id);
     if pda != *program_state_account.key {
@@ -180,7 +220,7 @@ fn process_initialize(
     
     // Create program state account
     let rent = Rent::get()?;
-    let rent_lamports = rent.minimum_balance(std::mem::size_of::<ProgramState>());
+    let rent_lamports = rent.minimum_balance(StakingProgramState::LEN);
     
     // Create account
     invoke_signed(
@@ -188,7 +228,7 @@ fn process_initialize(
             admin_account.key,
             program_state_account.key,
             rent_lamports,
-            std::mem::size_of::<ProgramState>() as u64,
+            StakingProgramState::LEN as u64,
             program_id,
         ),
         &[
@@ -200,8 +240,8 @@ fn process_initialize(
     )?;
     
     // Initialize program state - CRITICAL FIX: Add protocol version
-    let program_state = ProgramState {
-        protocol_version: PROTOCOL_VERSION, // Initialize with current version
+    let program_state = StakingProgramState {
+        protocol_version: StakingProgramState::PROTOCOL_VERSION, // Initialize with current version
         admin: *admin_account.key,
         yot_mint,
         yos_mint,
@@ -210,9 +250,9 @@ fn process_initialize(
     };
     
     // Save program state
-    program_state.serialize(&mut *program_state_account.try_borrow_mut_data()?)?;
+    program_state.pack(&mut program_state_account.try_borrow_mut_data()?[..])?;
     
-    msg!("Staking program initialized successfully with protocol version {}", PROTOCOL_VERSION);
+    msg!("Staking program initialized successfully with protocol version {}", StakingProgramState::PROTOCOL_VERSION);
     
     Ok(())
 }
@@ -245,7 +285,7 @@ fn process_stake(
     }
     
     // Get program state
-    let program_state = ProgramState::try_from_slice(&program_state_account.data.borrow())?;
+    let program_state = StakingProgramState::unpack(&program_state_account.data.borrow())?;
     
     // Verify token accounts
     let user_token_account = TokenAccount::unpack(&user_yot_token_account.data.borrow())?;
@@ -264,7 +304,7 @@ fn process_stake(
     
     // Get clock for timestamp
     let clock = Clock::from_account_info(clock)?;
-    let current_time = clock.unix_timestamp;
+    let current_time = clock_unix_timestamp(&clock);
     
     // Calculate staking account PDA
     let seeds = [
@@ -389,11 +429,15 @@ fn process_unstake(
     }
     
     // Get program state - IMPORTANT: We need this to get the CURRENT staking rate
-    let program_state = ProgramState::try_from_slice(&program_state_account.data.borrow())?;
+    let program_state = StakingProgramState::unpack(&program_state_account.data.borrow())?;
+    
+    // Unstaked YOT and rewards only go to the user's own accounts
+    verify_user_token_account(user_yot_token_account, &program_state.yot_mint, user_account.key)?;
+    verify_user_token_account(user_yos_token_account, &program_state.yos_mint, user_account.key)?;
     
     // Get current time
     let clock = Clock::from_account_info(clock)?;
-    let current_time = clock.unix_timestamp;
+    let current_time = clock_unix_timestamp(&clock);
     
     // Calculate time staked since last harvest
     let time_staked_seconds = current_time.checked_sub(staking_data.last_harvest_time)
@@ -570,7 +614,10 @@ fn process_harvest(
     }
     
     // Get program state
-    let program_state = ProgramState::try_from_slice(&program_state_account.data.borrow())?;
+    let program_state = StakingProgramState::unpack(&program_state_account.data.borrow())?;
+    
+    // Rewards only go to the user's own YOS account
+    verify_user_token_account(user_yos_token_account, &program_state.yos_mint, user_account.key)?;
     
     // Get staking data
     let mut staking_data = StakingAccount::try_from_slice(&user_staking_account.data.borrow())?;
@@ -587,7 +634,7 @@ fn process_harvest(
     
     // Get current time
     let clock = Clock::from_account_info(clock)?;
-    let current_time = clock.unix_timestamp;
+    let current_time = clock_unix_timestamp(&clock);
     
     // Calculate time staked since last harvest
     let time_staked_seconds = current_time.checked_sub(staking_data.last_harvest_time)
@@ -684,7 +731,7 @@ fn process_harvest(
 }
 
 fn process_update_parameters(
-    program_id: &Pubkey,
+    _program_id: &Pubkey,
     accounts: &[AccountInfo],
     stake_rate_per_second: u64,
     harvest_threshold: u64,
@@ -701,7 +748,7 @@ fn process_update_parameters(
     }
     
     // Get program state
-    let mut program_state = ProgramState::try_from_slice(&program_state_account.data.borrow())?;
+    let mut program_state = StakingProgramState::unpack(&program_state_account.data.borrow())?;
     
     // Verify caller is admin
     if program_state.admin != *admin_account.key {
@@ -713,7 +760,7 @@ fn process_update_parameters(
     program_state.harvest_threshold = harvest_threshold;
     
     // Save updated program state
-    program_state.serialize(&mut *program_state_account.try_borrow_mut_data()?)?;
+    program_state.pack(&mut program_state_account.try_borrow_mut_data()?[..])?;
     
     // Log updates
     msg!("Updated stake rate to {}% per second", stake_rate_per_second as f64 / 1_000_000.0);
@@ -722,7 +769,18 @@ fn process_update_parameters(
     Ok(())
 }
 
+// Fail unless a token account holds `mint` and is owned by `user`
+fn verify_user_token_account(token_account: &AccountInfo, mint: &Pubkey, user: &Pubkey) -> ProgramResult {
+    let account = TokenAccount::unpack(&token_account.data.borrow())?;
+    if account.owner != *user || account.mint != *mint {
+        msg!("Error: Token account {} is not a {} account of {}", token_account.key, mint, user);
+        return Err(ProgramError::InvalidAccountData);
+    }
+    Ok(())
+}
+
 // Helper function to calculate rewards accurately using the same formula as actual contract code
+#[allow(dead_code)]
 fn calculate_rewards(
     staked_amount: u64,
     stake_rate_per_second: u64,
@@ -0,0 +1,1017 @@
+//! Program state accounts
+//!
+//! Every processor reads and writes its program state through this module, so no file keeps
+//! its own copy of a layout. Each deployed program has exactly one state struct:
+//!
+//! - [`ProgramState`]: the multi-hub swap program's state at `[b"state"]`. `unpack` reads the
+//!   released `LEN_V1` and `LEN_V2` layouts by their length and fills the missing fields with
+//!   defaults, and `pack` always writes the current `LEN` layout.
+//! - [`StakingProgramState`]: the staking program's state at `[b"program_state"]`, versioned by
+//!   its leading `protocol_version` byte. `unpack` also reads the unversioned layout of the
+//!   first staking deployments.
+//!
+//! The `ProgramState` structs under `archive/legacy` are the swap `LEN_V1` layout and the
+//! unversioned staking layout; both are read here.
+
+use arrayref::{array_mut_ref, array_ref, array_refs, mut_array_refs};
+use solana_program::{msg, program_error::ProgramError, pubkey::Pubkey};
+
+/// Default oracle staleness limit (~1 minute of slots)
+pub const DEFAULT_ORACLE_MAX_STALENESS_SLOTS: u64 = 150;
+/// Default oracle confidence limit (2% of the price)
+pub const DEFAULT_ORACLE_MAX_CONFIDENCE_BPS: u64 = 200;
+
+// Multi-hub swap program state, stored at [b"state"], with manual serialization
+pub struct ProgramState {
+    pub admin: Pubkey,                 // Owner: rotates the other roles and holds any role left unset
+    pub yot_mint: Pubkey,
+    pub yos_mint: Pubkey,
+    pub lp_contribution_rate: u64,     // Rate for liquidity contribution (20%)
+    pub admin_fee_rate: u64,           // Admin fee rate (0%)
+    pub yos_cashback_rate: u64,        // YOS cashback rate (5%)
+    pub swap_fee_rate: u64,            // Swap fee rate (1%)
+    pub referral_rate: u64,            // Referral rate (0%)
+    pub liquidity_wallet: Pubkey,      // Central liquidity wallet
+    pub liquidity_threshold: u64,      // Threshold for auto LP addition (in lamports, e.g., 0.1 SOL = 100,000,000 lamports)
+    pub recovery_admin: Pubkey,        // Second key that must co-sign state repairs (default = not configured)
+    pub emission_mode: u8,             // Reward emission mode (EMISSION_MODE_FLAT or EMISSION_MODE_PRO_RATA)
+    pub weekly_emission_budget: u64,   // YOS emitted per claim period across all contributors in pro-rata mode
+    pub allowlist_enabled: u8,         // When non-zero, swaps and contributions require an allowlisted user
+    pub oracle_max_staleness_slots: u64, // Oracle prices older than this many slots are rejected
+    pub oracle_max_confidence_bps: u64,  // Maximum confidence interval, in basis points of the price
+    pub oracle_fallback: u8,           // ORACLE_FALLBACK_REJECT or ORACLE_FALLBACK_TWAP
+    pub cashback_mint_selector: u8,    // CASHBACK_YOS, CASHBACK_YOT, CASHBACK_NONE or CASHBACK_YOS_ACCRUED
+    pub cashback_treasury: Pubkey,     // Program-owned YOT account paying cashback in CASHBACK_YOT mode
+    pub sol_fee_bps: u64,              // Admin commission on SOL-in swaps, in basis points, paid to the SOL fee vault
+    pub destination_policy: u8,        // DESTINATION_POLICY_REJECT or DESTINATION_POLICY_DEFER for frozen/closed accounts
+    pub max_price_impact_bps: u64,     // Largest price impact a single-pool swap may have, in basis points (0 = no cap)
+    pub central_cap_policy: u8,        // CENTRAL_CAP_OFF, CENTRAL_CAP_ROUTE_TO_POOL or CENTRAL_CAP_REVERT
+    pub state_bump: u8,                // Bump of the [b"state"] PDA (0 = not recorded)
+    pub authority_bump: u8,            // Bump of the [b"authority"] PDA (0 = not recorded)
+    pub rebate_bps: u64,               // Share of YOT/YOS swap fees accruing as stake-weighted YOS rebates (0 = off)
+    pub ve_fee_tier_weight: u64,       // YOS lock weight that qualifies for the discounted pool fee tier (0 = no tier)
+    pub ve_fee_discount_bps: u64,      // Pool fee discount for the tier, in basis points of the fee
+    pub dust_threshold: u64,           // Contributions below this can be closed by anyone (0 = off)
+    pub dust_bounty_lamports: u64,     // Paid from the SOL fee vault to whoever closes a dust contribution
+    pub liquidity_price_oracle: Pubkey, // Price account checked before central wallet liquidity adds (default = TWAP only)
+    pub max_liquidity_deviation_bps: u64, // Largest spot deviation from the reference price a liquidity add may have (0 = off)
+    pub sol_pool: Pubkey,              // SOL-YOT pool SOL account used by swap and stake (default = not set)
+    pub yot_pool: Pubkey,              // SOL-YOT pool YOT account
+    pub sol_yot_lp_mint: Pubkey,       // LP mint of the SOL-YOT pool
+    pub lp_farm_program: Pubkey,       // Staking program deployment that farms SOL-YOT LP tokens
+    pub swap_hook_program: Pubkey,     // Program CPI'd with every SwapResult (default = no hook)
+    pub pending_swap_hook: Pubkey,     // Proposed hook program waiting for the timelock
+    pub swap_hook_effective_at: i64,   // Unix time the pending hook may be applied (0 = nothing pending)
+    pub swap_hook_policy: u8,          // SWAP_HOOK_REQUIRED or SWAP_HOOK_BEST_EFFORT
+    pub min_swap_amount_lamports: u64, // Smallest SOL input a swap accepts, in lamports (0 = no minimum)
+    pub min_swap_amount_tokens: u64,   // Smallest token input a swap accepts, in base units (0 = no minimum)
+    pub liquidity_policy: u8,          // LIQUIDITY_POLICY_CENTRAL_WALLET, _DIRECT_TO_POOL or _BURN for the 20% cut
+    pub cashback_base: u8,             // CASHBACK_BASE_YOT_LEG, _INPUT, _OUTPUT or _ORACLE
+    pub referral_bonus_yos: u64,       // One-time YOS paid to a user and their first referrer (0 = off)
+    pub inactivity_periods: u8,        // Claim periods without a claim or contribution before rewards pause (0 = never)
+    pub liquidity_pool_selection: u8,  // LIQUIDITY_POOL_PROTOCOL_OWNED or _DEEPEST, for DIRECT_TO_POOL cuts
+    pub shutdown_recipient: Pubkey,    // Wallet receiving protocol-owned assets at shutdown (default = none announced)
+    pub shutdown_effective_at: i64,    // When the announced shutdown may execute (0 = none announced)
+    pub shut_down: u8,                 // 1 once ExecuteShutdown has run; swaps are disabled for good
+    pub disabled_instructions: u128,   // Bit n set = instruction tag n is disabled
+    pub pending_disabled_instructions: u128, // Proposed flags waiting for the timelock
+    pub instruction_flags_effective_at: i64, // Unix time the pending flags may be applied (0 = nothing pending)
+    pub scheduled_emission: u8,        // 1 once SetEmissionSchedule has run; the flat rate then follows the schedule PDA
+    pub yot_usd_oracle: Pubkey,        // Price account in micro-USD per YOT base unit for USD snapshots (default = off)
+    pub yos_usd_oracle: Pubkey,        // Price account in micro-USD per YOS base unit for USD snapshots (default = off)
+    pub operator: Pubkey,              // Parameter updates and pool management (default = the owner)
+    pub pauser: Pubkey,                // Pool pauses and instruction flags (default = the owner)
+    pub treasurer: Pubkey,             // Fee sweeps and buybacks (default = the owner)
+    pub auto_add_liquidity: u8,        // When non-zero, swaps that leave the central wallet over the threshold add its liquidity
+    pub market_maker: Pubkey,          // Signer of RFQ quotes (default = RFQ swaps disabled)
+    pub lp_contributions: u8,          // When non-zero, liquidity cuts kept in the SOL-YOT pool are held for contributors as LP
+    pub referral_epoch_bonus_yos: u64, // YOS shared by each referral epoch's top referrers (0 = no leaderboard bonus)
+    pub insurance_fee_bps: u64,        // Share of each pool swap, in bps of the input, set aside for the insurance fund out of the swap fee (0 = off)
+    pub cashback_rate_min: u64,        // Lowest yos_cashback_rate the cashback controller may set, in percent
+    pub cashback_rate_max: u64,        // Highest yos_cashback_rate the cashback controller may set, in percent
+    pub cashback_target_price: u64,    // YOS price in YOT the controller steers toward, PRICE_SCALE fixed point (0 = controller off)
+    pub bridge_operator: Pubkey,       // Completes and cancels bridge intents (default = bridging off)
+    pub sell_contribution_side: u8,    // SELL_CONTRIBUTION_SOL_OUTPUT or _YOT_INPUT for YOT to SOL swaps
+    pub config_version: u64,           // Bumped by every parameter change
+    pub lp_rate_ramp_target: u64,      // lp_contribution_rate the ramp ends at
+    pub lp_rate_ramp_start: i64,       // Unix time the ramp left lp_contribution_rate
+    pub lp_rate_ramp_end: i64,         // Unix time the ramp reaches its target (0 = no ramp)
+    pub swap_fee_ramp_target: u64,     // swap_fee_rate the ramp ends at
+    pub swap_fee_ramp_start: i64,      // Unix time the ramp left swap_fee_rate
+    pub swap_fee_ramp_end: i64,        // Unix time the ramp reaches its target (0 = no ramp)
+    pub crank_fee_bps: u64,            // Share of the value a permissionless crank moves paid to its caller (0 = off)
+    pub crank_fee_cap_lamports: u64,   // Most a crank paying in SOL may earn per call, in lamports
+    pub crank_fee_cap_tokens: u64,     // Most a crank paying in tokens may earn per call, in base units
+    pub central_exposure_cap_lamports: u64, // Unconverted central wallet holdings, in lamports, above which cuts stay in the pool (0 = no cap)
+    pub commit_reveal_window_slots: u64, // Slots after CommitSwap in which RevealSwap may run it (0 = commit-reveal off)
+    pub central_liquidity_lock: u8,    // 1 once InitCentralLiquidityState ran; central wallet operations then take its lock
+    pub disabled_instructions_high: u128, // Bit n set = instruction tag 128 + n is disabled
+    pub pending_disabled_instructions_high: u128, // Proposed flags for tags 128 and up
+}
+
+/// Admin roles; each admin instruction is gated on exactly one
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum AdminRole {
+    Owner = 0,
+    Operator = 1,
+    Pauser = 2,
+    Treasurer = 3,
+    BridgeOperator = 4,
+}
+
+impl AdminRole {
+    pub fn from_u8(value: u8) -> Option<Self> {
+        match value {
+            0 => Some(Self::Owner),
+            1 => Some(Self::Operator),
+            2 => Some(Self::Pauser),
+            3 => Some(Self::Treasurer),
+            4 => Some(Self::BridgeOperator),
+            _ => None,
+        }
+    }
+}
+
+impl ProgramState {
+    // Original layout without liquidity_wallet and liquidity_threshold
+    pub const LEN_V1: usize = 32 + 32 + 32 + 8 + 8 + 8 + 8 + 8; // 3 pubkeys + 5 u64s
+    // Released layout with the central liquidity wallet and threshold
+    pub const LEN_V2: usize = Self::LEN_V1 + 32 + 8; // 4 pubkeys + 6 u64s
+    // Current layout, every field after the released V2 ones
+    pub const LEN: usize = Self::LEN_V2 + 17 * 32 + 19 + 27 * 8 + 7 * 8 + 4 * 16; // 21 pubkeys + 19 u8s + 33 u64s + 7 i64s + 4 u128s
+    // Released layouts before the current one; an account this long can be migrated
+    pub const LEGACY_LENS: [usize; 2] = [Self::LEN_V1, Self::LEN_V2];
+    // Offset of state_bump, read to find the state account before unpacking it
+    pub const STATE_BUMP_OFFSET: usize = Self::LEN_V2 + 32 + 1 + 8 + 1 + 8 + 8 + 1 + 1 + 32 + 8 + 1 + 8 + 1;
+
+    // Every field in packed order with its size, for logging parameter changes
+    pub const FIELDS: [(&'static str, usize); 84] = [
+        ("admin", 32),
+        ("yot_mint", 32),
+        ("yos_mint", 32),
+        ("lp_contribution_rate", 8),
+        ("admin_fee_rate", 8),
+        ("yos_cashback_rate", 8),
+        ("swap_fee_rate", 8),
+        ("referral_rate", 8),
+        ("liquidity_wallet", 32),
+        ("liquidity_threshold", 8),
+        ("recovery_admin", 32),
+        ("emission_mode", 1),
+        ("weekly_emission_budget", 8),
+        ("allowlist_enabled", 1),
+        ("oracle_max_staleness_slots", 8),
+        ("oracle_max_confidence_bps", 8),
+        ("oracle_fallback", 1),
+        ("cashback_mint_selector", 1),
+        ("cashback_treasury", 32),
+        ("sol_fee_bps", 8),
+        ("destination_policy", 1),
+        ("max_price_impact_bps", 8),
+        ("central_cap_policy", 1),
+        ("state_bump", 1),
+        ("authority_bump", 1),
+        ("rebate_bps", 8),
+        ("ve_fee_tier_weight", 8),
+        ("ve_fee_discount_bps", 8),
+        ("dust_threshold", 8),
+        ("dust_bounty_lamports", 8),
+        ("liquidity_price_oracle", 32),
+        ("max_liquidity_deviation_bps", 8),
+        ("sol_pool", 32),
+        ("yot_pool", 32),
+        ("sol_yot_lp_mint", 32),
+        ("lp_farm_program", 32),
+        ("swap_hook_program", 32),
+        ("pending_swap_hook", 32),
+        ("swap_hook_effective_at", 8),
+        ("swap_hook_policy", 1),
+        ("min_swap_amount_lamports", 8),
+        ("min_swap_amount_tokens", 8),
+        ("liquidity_policy", 1),
+        ("cashback_base", 1),
+        ("referral_bonus_yos", 8),
+        ("inactivity_periods", 1),
+        ("liquidity_pool_selection", 1),
+        ("shutdown_recipient", 32),
+        ("shutdown_effective_at", 8),
+        ("shut_down", 1),
+        ("disabled_instructions", 16),
+        ("pending_disabled_instructions", 16),
+        ("instruction_flags_effective_at", 8),
+        ("scheduled_emission", 1),
+        ("yot_usd_oracle", 32),
+        ("yos_usd_oracle", 32),
+        ("operator", 32),
+        ("pauser", 32),
+        ("treasurer", 32),
+        ("auto_add_liquidity", 1),
+        ("market_maker", 32),
+        ("lp_contributions", 1),
+        ("referral_epoch_bonus_yos", 8),
+        ("insurance_fee_bps", 8),
+        ("cashback_rate_min", 8),
+        ("cashback_rate_max", 8),
+        ("cashback_target_price", 8),
+        ("bridge_operator", 32),
+        ("sell_contribution_side", 1),
+        ("config_version", 8),
+        ("lp_rate_ramp_target", 8),
+        ("lp_rate_ramp_start", 8),
+        ("lp_rate_ramp_end", 8),
+        ("swap_fee_ramp_target", 8),
+        ("swap_fee_ramp_start", 8),
+        ("swap_fee_ramp_end", 8),
+        ("crank_fee_bps", 8),
+        ("crank_fee_cap_lamports", 8),
+        ("crank_fee_cap_tokens", 8),
+        ("central_exposure_cap_lamports", 8),
+        ("commit_reveal_window_slots", 8),
+        ("central_liquidity_lock", 1),
+        ("disabled_instructions_high", 16),
+        ("pending_disabled_instructions_high", 16),
+    ];
+    
+    // Flat 2% of each contribution per claim period
+    pub const EMISSION_MODE_FLAT: u8 = 0;
+    // Fixed weekly budget split pro-rata using GlobalContributionState.acc_reward_per_share
+    pub const EMISSION_MODE_PRO_RATA: u8 = 1;
+    
+    // Reject the trade when the oracle price fails validation
+    pub const ORACLE_FALLBACK_REJECT: u8 = 0;
+    // Price against the pool TWAP when the oracle price fails validation
+    pub const ORACLE_FALLBACK_TWAP: u8 = 1;
+    
+    // Cashback minted in YOS by the program authority
+    pub const CASHBACK_YOS: u8 = 0;
+    // Cashback paid in YOT from the program-owned cashback treasury
+    pub const CASHBACK_YOT: u8 = 1;
+    // No cashback
+    pub const CASHBACK_NONE: u8 = 2;
+    // YOS cashback credited to the user history and minted when the user sends ClaimCashback
+    pub const CASHBACK_YOS_ACCRUED: u8 = 3;
+    
+    // Fail the swap with DestinationFrozen when the cashback account cannot receive tokens
+    pub const DESTINATION_POLICY_REJECT: u8 = 0;
+    // Credit undeliverable cashback to the user's pending balance in the user history PDA
+    pub const DESTINATION_POLICY_DEFER: u8 = 1;
+    
+    // Central liquidity wallet deposits are not capped
+    pub const CENTRAL_CAP_OFF: u8 = 0;
+    // Contribution above the mint's cap stays in the pool reserve instead of the wallet
+    pub const CENTRAL_CAP_ROUTE_TO_POOL: u8 = 1;
+    // A swap whose contribution would exceed the mint's cap fails with CentralWalletCapExceeded
+    pub const CENTRAL_CAP_REVERT: u8 = 2;
+    
+    // Swaps fail unless the registered hook program is passed (and the hook succeeds)
+    pub const SWAP_HOOK_REQUIRED: u8 = 0;
+    // Swaps that do not pass the hook program skip the hook
+    pub const SWAP_HOOK_BEST_EFFORT: u8 = 1;
+    
+    // The 20% liquidity cut goes to the central liquidity wallet
+    pub const LIQUIDITY_POLICY_CENTRAL_WALLET: u8 = 0;
+    // The 20% liquidity cut is deposited one-sided into the SOL-YOT pool's YOT reserve
+    pub const LIQUIDITY_POLICY_DIRECT_TO_POOL: u8 = 1;
+    // The 20% liquidity cut is burned
+    pub const LIQUIDITY_POLICY_BURN: u8 = 2;
+    
+    // DIRECT_TO_POOL cuts go to the pinned SOL-YOT pool (yot_pool)
+    pub const LIQUIDITY_POOL_PROTOCOL_OWNED: u8 = 0;
+    // DIRECT_TO_POOL cuts go to the SOL-YOT pool with the largest YOT reserve among the pinned
+    // pool and the unpaused registered SOL-YOT pools passed to the swap
+    pub const LIQUIDITY_POOL_DEEPEST: u8 = 1;
+    
+    // YOT to SOL swaps send 20% of the SOL output to the central liquidity wallet
+    pub const SELL_CONTRIBUTION_SOL_OUTPUT: u8 = 0;
+    // YOT to SOL swaps set 20% of the YOT input aside before pricing; the user gets the whole
+    // SOL output of the rest
+    pub const SELL_CONTRIBUTION_YOT_INPUT: u8 = 1;
+    
+    // SetParameterRamp moves lp_contribution_rate
+    pub const RAMP_LP_CONTRIBUTION_RATE: u8 = 0;
+    // SetParameterRamp moves swap_fee_rate
+    pub const RAMP_SWAP_FEE_RATE: u8 = 1;
+    
+    // Cashback is paid on the YOT leg of the swap (output of SOL to YOT, input of YOT to SOL)
+    pub const CASHBACK_BASE_YOT_LEG: u8 = 0;
+    // Cashback is paid on the swap input, SOL valued at the pool price before the swap
+    pub const CASHBACK_BASE_INPUT: u8 = 1;
+    // Cashback is paid on the swap output, SOL valued at the pool price before the swap
+    pub const CASHBACK_BASE_OUTPUT: u8 = 2;
+    // Cashback is paid on the SOL leg valued at the reference price (oracle, else TWAP)
+    pub const CASHBACK_BASE_ORACLE: u8 = 3;
+    
+    /// Key holding `role`; a role that was never assigned falls back to the owner
+    pub fn role_holder(&self, role: AdminRole) -> Pubkey {
+        let holder = match role {
+            AdminRole::Owner => self.admin,
+            AdminRole::Operator => self.operator,
+            AdminRole::Pauser => self.pauser,
+            AdminRole::Treasurer => self.treasurer,
+            AdminRole::BridgeOperator => self.bridge_operator,
+        };
+        if holder == Pubkey::default() { self.admin } else { holder }
+    }
+
+    /// Value of a ramp from `from` at `start` to `target` at `end`, linear in between
+    /// A ramp with no end (0) holds `from`; one past its end holds `target`
+    pub fn ramped_value(from: u64, target: u64, start: i64, end: i64, now: i64) -> u64 {
+        if end == 0 || now <= start {
+            return from;
+        }
+        if now >= end || end <= start {
+            return target;
+        }
+        let elapsed = now.abs_diff(start) as u128;
+        let duration = end.abs_diff(start) as u128;
+        let step = (from.abs_diff(target) as u128).saturating_mul(elapsed).checked_div(duration).unwrap_or(0);
+        let step = u64::try_from(step).unwrap_or(u64::MAX);
+        if target >= from { from.saturating_add(step) } else { from.saturating_sub(step) }
+    }
+
+    /// lp_contribution_rate in effect at `now`, following its ramp while one is set
+    pub fn effective_lp_contribution_rate(&self, now: i64) -> u64 {
+        Self::ramped_value(self.lp_contribution_rate, self.lp_rate_ramp_target, self.lp_rate_ramp_start, self.lp_rate_ramp_end, now)
+    }
+
+    /// swap_fee_rate in effect at `now`, following its ramp while one is set
+    pub fn effective_swap_fee_rate(&self, now: i64) -> u64 {
+        Self::ramped_value(self.swap_fee_rate, self.swap_fee_ramp_target, self.swap_fee_ramp_start, self.swap_fee_ramp_end, now)
+    }
+
+    /// Fields that differ between two packed states, with their old and new bytes
+    /// config_version is left out, since every change moves it
+    pub fn changed_fields<'a>(before: &'a [u8], after: &'a [u8]) -> Vec<(&'static str, &'a [u8], &'a [u8])> {
+        let mut changes = Vec::new();
+        let mut offset = 0usize;
+        for (name, size) in Self::FIELDS {
+            let end = offset.saturating_add(size);
+            if let (Some(old), Some(new)) = (before.get(offset..end), after.get(offset..end)) {
+                if old != new && name != "config_version" {
+                    changes.push((name, old, new));
+                }
+            }
+            offset = end;
+        }
+        changes
+    }
+    
+    // Manual deserialization with backward compatibility handling
+    pub fn unpack(data: &[u8]) -> Result<Self, ProgramError> {
+        if data.len() < Self::LEN_V1 {
+            msg!("ERROR: Data too short even for old format: {} bytes", data.len());
+            return Err(ProgramError::InvalidAccountData);
+        }
+
+        let data_v1 = array_ref![data, 0, ProgramState::LEN_V1];
+        let (
+            admin,
+            yot_mint,
+            yos_mint,
+            lp_contribution_rate,
+            admin_fee_rate,
+            yos_cashback_rate,
+            swap_fee_rate,
+            referral_rate,
+        ) = array_refs![data_v1, 32, 32, 32, 8, 8, 8, 8, 8];
+
+        let mut state = Self {
+            admin: Pubkey::new_from_array(*admin),
+            yot_mint: Pubkey::new_from_array(*yot_mint),
+            yos_mint: Pubkey::new_from_array(*yos_mint),
+            lp_contribution_rate: u64::from_le_bytes(*lp_contribution_rate),
+            admin_fee_rate: u64::from_le_bytes(*admin_fee_rate),
+            yos_cashback_rate: u64::from_le_bytes(*yos_cashback_rate),
+            swap_fee_rate: u64::from_le_bytes(*swap_fee_rate),
+            referral_rate: u64::from_le_bytes(*referral_rate),
+            // Default values for fields missing from older layouts
+            liquidity_wallet: Pubkey::default(), // Will be updated in process_repair_program_state
+            liquidity_threshold: 100000000,      // Default 0.1 SOL
+            recovery_admin: Pubkey::default(),   // Must be configured with SetRecoveryAdmin
+            emission_mode: Self::EMISSION_MODE_FLAT,
+            weekly_emission_budget: 0,
+            allowlist_enabled: 0,
+            oracle_max_staleness_slots: DEFAULT_ORACLE_MAX_STALENESS_SLOTS,
+            oracle_max_confidence_bps: DEFAULT_ORACLE_MAX_CONFIDENCE_BPS,
+            oracle_fallback: Self::ORACLE_FALLBACK_REJECT,
+            cashback_mint_selector: Self::CASHBACK_YOS,
+            cashback_treasury: Pubkey::default(),
+            sol_fee_bps: 0,
+            destination_policy: Self::DESTINATION_POLICY_REJECT,
+            max_price_impact_bps: 0,
+            central_cap_policy: Self::CENTRAL_CAP_OFF,
+            state_bump: 0,
+            authority_bump: 0,
+            rebate_bps: 0,
+            ve_fee_tier_weight: 0,
+            ve_fee_discount_bps: 0,
+            dust_threshold: 0,
+            dust_bounty_lamports: 0,
+            liquidity_price_oracle: Pubkey::default(),
+            max_liquidity_deviation_bps: 0,
+            sol_pool: Pubkey::default(),
+            yot_pool: Pubkey::default(),
+            sol_yot_lp_mint: Pubkey::default(),
+            lp_farm_program: Pubkey::default(),
+            swap_hook_program: Pubkey::default(),
+            pending_swap_hook: Pubkey::default(),
+            swap_hook_effective_at: 0,
+            swap_hook_policy: Self::SWAP_HOOK_REQUIRED,
+            min_swap_amount_lamports: 0,
+            min_swap_amount_tokens: 0,
+            liquidity_policy: Self::LIQUIDITY_POLICY_CENTRAL_WALLET,
+            cashback_base: Self::CASHBACK_BASE_YOT_LEG,
+            referral_bonus_yos: 0,
+            inactivity_periods: 0,
+            liquidity_pool_selection: Self::LIQUIDITY_POOL_PROTOCOL_OWNED,
+            shutdown_recipient: Pubkey::default(),
+            shutdown_effective_at: 0,
+            shut_down: 0,
+            disabled_instructions: 0,
+            pending_disabled_instructions: 0,
+            instruction_flags_effective_at: 0,
+            scheduled_emission: 0,
+            yot_usd_oracle: Pubkey::default(),
+            yos_usd_oracle: Pubkey::default(),
+            operator: Pubkey::default(),
+            pauser: Pubkey::default(),
+            treasurer: Pubkey::default(),
+            auto_add_liquidity: 0,
+            market_maker: Pubkey::default(),
+            lp_contributions: 0,
+            referral_epoch_bonus_yos: 0,
+            insurance_fee_bps: 0,
+            cashback_rate_min: 0,
+            cashback_rate_max: 0,
+            cashback_target_price: 0,
+            bridge_operator: Pubkey::default(),
+            sell_contribution_side: 0,
+            config_version: 0,
+            lp_rate_ramp_target: 0,
+            lp_rate_ramp_start: 0,
+            lp_rate_ramp_end: 0,
+            swap_fee_ramp_target: 0,
+            swap_fee_ramp_start: 0,
+            swap_fee_ramp_end: 0,
+            crank_fee_bps: 0,
+            crank_fee_cap_lamports: 0,
+            crank_fee_cap_tokens: 0,
+            central_exposure_cap_lamports: 0,
+            commit_reveal_window_slots: 0,
+            central_liquidity_lock: 0,
+            disabled_instructions_high: 0,
+            pending_disabled_instructions_high: 0,
+        };
+
+        if data.len() < Self::LEN_V2 {
+            // Handle older program state format (backward compatibility)
+            msg!("Program state data too short (old format detected)");
+            msg!("Using old format data + default values for new fields");
+            return Ok(state);
+        }
+
+        let data_v2 = array_ref![data, ProgramState::LEN_V1, 40];
+        let (liquidity_wallet, liquidity_threshold) = array_refs![data_v2, 32, 8];
+        state.liquidity_wallet = Pubkey::new_from_array(*liquidity_wallet);
+        state.liquidity_threshold = u64::from_le_bytes(*liquidity_threshold);
+
+        if data.len() < Self::LEN {
+            msg!("Program state is in the released V2 layout (defaults for newer fields)");
+            return Ok(state);
+        }
+
+        let data_current = array_ref![data, 0, ProgramState::LEN];
+        let (
+            _,
+            recovery_admin,
+            emission_mode,
+            weekly_emission_budget,
+            allowlist_enabled,
+            oracle_max_staleness_slots,
+            oracle_max_confidence_bps,
+            oracle_fallback,
+            cashback_mint_selector,
+            cashback_treasury,
+            sol_fee_bps,
+            destination_policy,
+            max_price_impact_bps,
+            central_cap_policy,
+            state_bump,
+            authority_bump,
+            rebate_bps,
+            ve_fee_tier_weight,
+            ve_fee_discount_bps,
+            dust_threshold,
+            dust_bounty_lamports,
+            liquidity_price_oracle,
+            max_liquidity_deviation_bps,
+            sol_pool,
+            yot_pool,
+            sol_yot_lp_mint,
+            lp_farm_program,
+            swap_hook_program,
+            pending_swap_hook,
+            swap_hook_effective_at,
+            swap_hook_policy,
+            min_swap_amount_lamports,
+            min_swap_amount_tokens,
+            liquidity_policy,
+            cashback_base,
+            referral_bonus_yos,
+            inactivity_periods,
+            liquidity_pool_selection,
+            shutdown_recipient,
+            shutdown_effective_at,
+            shut_down,
+            disabled_instructions,
+            pending_disabled_instructions,
+            instruction_flags_effective_at,
+            scheduled_emission,
+            yot_usd_oracle,
+            yos_usd_oracle,
+            operator,
+            pauser,
+            treasurer,
+            auto_add_liquidity,
+            market_maker,
+            lp_contributions,
+            referral_epoch_bonus_yos,
+            insurance_fee_bps,
+            cashback_rate_min,
+            cashback_rate_max,
+            cashback_target_price,
+            bridge_operator,
+            sell_contribution_side,
+            config_version,
+            lp_rate_ramp_target,
+            lp_rate_ramp_start,
+            lp_rate_ramp_end,
+            swap_fee_ramp_target,
+            swap_fee_ramp_start,
+            swap_fee_ramp_end,
+            crank_fee_bps,
+            crank_fee_cap_lamports,
+            crank_fee_cap_tokens,
+            central_exposure_cap_lamports,
+            commit_reveal_window_slots,
+            central_liquidity_lock,
+            disabled_instructions_high,
+            pending_disabled_instructions_high,
+        ) = array_refs![data_current, ProgramState::LEN_V2, 32, 1, 8, 1, 8, 8, 1, 1, 32, 8, 1, 8, 1, 1, 1, 8, 8, 8, 8, 8, 32, 8, 32, 32, 32, 32, 32, 32, 8, 1, 8, 8, 1, 1, 8, 1, 1, 32, 8, 1, 16, 16, 8, 1, 32, 32, 32, 32, 32, 1, 32, 1, 8, 8, 8, 8, 8, 32, 1, 8, 8, 8, 8, 8, 8, 8, 8, 8, 8, 8, 8, 1, 16, 16];
+
+        state.recovery_admin = Pubkey::new_from_array(*recovery_admin);
+        state.emission_mode = emission_mode[0];
+        state.weekly_emission_budget = u64::from_le_bytes(*weekly_emission_budget);
+        state.allowlist_enabled = allowlist_enabled[0];
+        state.oracle_max_staleness_slots = u64::from_le_bytes(*oracle_max_staleness_slots);
+        state.oracle_max_confidence_bps = u64::from_le_bytes(*oracle_max_confidence_bps);
+        state.oracle_fallback = oracle_fallback[0];
+        state.cashback_mint_selector = cashback_mint_selector[0];
+        state.cashback_treasury = Pubkey::new_from_array(*cashback_treasury);
+        state.sol_fee_bps = u64::from_le_bytes(*sol_fee_bps);
+        state.destination_policy = destination_policy[0];
+        state.max_price_impact_bps = u64::from_le_bytes(*max_price_impact_bps);
+        state.central_cap_policy = central_cap_policy[0];
+        state.state_bump = state_bump[0];
+        state.authority_bump = authority_bump[0];
+        state.rebate_bps = u64::from_le_bytes(*rebate_bps);
+        state.ve_fee_tier_weight = u64::from_le_bytes(*ve_fee_tier_weight);
+        state.ve_fee_discount_bps = u64::from_le_bytes(*ve_fee_discount_bps);
+        state.dust_threshold = u64::from_le_bytes(*dust_threshold);
+        state.dust_bounty_lamports = u64::from_le_bytes(*dust_bounty_lamports);
+        state.liquidity_price_oracle = Pubkey::new_from_array(*liquidity_price_oracle);
+        state.max_liquidity_deviation_bps = u64::from_le_bytes(*max_liquidity_deviation_bps);
+        state.sol_pool = Pubkey::new_from_array(*sol_pool);
+        state.yot_pool = Pubkey::new_from_array(*yot_pool);
+        state.sol_yot_lp_mint = Pubkey::new_from_array(*sol_yot_lp_mint);
+        state.lp_farm_program = Pubkey::new_from_array(*lp_farm_program);
+        state.swap_hook_program = Pubkey::new_from_array(*swap_hook_program);
+        state.pending_swap_hook = Pubkey::new_from_array(*pending_swap_hook);
+        state.swap_hook_effective_at = i64::from_le_bytes(*swap_hook_effective_at);
+        state.swap_hook_policy = swap_hook_policy[0];
+        state.min_swap_amount_lamports = u64::from_le_bytes(*min_swap_amount_lamports);
+        state.min_swap_amount_tokens = u64::from_le_bytes(*min_swap_amount_tokens);
+        state.liquidity_policy = liquidity_policy[0];
+        state.cashback_base = cashback_base[0];
+        state.referral_bonus_yos = u64::from_le_bytes(*referral_bonus_yos);
+        state.inactivity_periods = inactivity_periods[0];
+        state.liquidity_pool_selection = liquidity_pool_selection[0];
+        state.shutdown_recipient = Pubkey::new_from_array(*shutdown_recipient);
+        state.shutdown_effective_at = i64::from_le_bytes(*shutdown_effective_at);
+        state.shut_down = shut_down[0];
+        state.disabled_instructions = u128::from_le_bytes(*disabled_instructions);
+        state.pending_disabled_instructions = u128::from_le_bytes(*pending_disabled_instructions);
+        state.instruction_flags_effective_at = i64::from_le_bytes(*instruction_flags_effective_at);
+        state.scheduled_emission = scheduled_emission[0];
+        state.yot_usd_oracle = Pubkey::new_from_array(*yot_usd_oracle);
+        state.yos_usd_oracle = Pubkey::new_from_array(*yos_usd_oracle);
+        state.operator = Pubkey::new_from_array(*operator);
+        state.pauser = Pubkey::new_from_array(*pauser);
+        state.treasurer = Pubkey::new_from_array(*treasurer);
+        state.auto_add_liquidity = auto_add_liquidity[0];
+        state.market_maker = Pubkey::new_from_array(*market_maker);
+        state.lp_contributions = lp_contributions[0];
+        state.referral_epoch_bonus_yos = u64::from_le_bytes(*referral_epoch_bonus_yos);
+        state.insurance_fee_bps = u64::from_le_bytes(*insurance_fee_bps);
+        state.cashback_rate_min = u64::from_le_bytes(*cashback_rate_min);
+        state.cashback_rate_max = u64::from_le_bytes(*cashback_rate_max);
+        state.cashback_target_price = u64::from_le_bytes(*cashback_target_price);
+        state.bridge_operator = Pubkey::new_from_array(*bridge_operator);
+        state.sell_contribution_side = sell_contribution_side[0];
+        state.config_version = u64::from_le_bytes(*config_version);
+        state.lp_rate_ramp_target = u64::from_le_bytes(*lp_rate_ramp_target);
+        state.lp_rate_ramp_start = i64::from_le_bytes(*lp_rate_ramp_start);
+        state.lp_rate_ramp_end = i64::from_le_bytes(*lp_rate_ramp_end);
+        state.swap_fee_ramp_target = u64::from_le_bytes(*swap_fee_ramp_target);
+        state.swap_fee_ramp_start = i64::from_le_bytes(*swap_fee_ramp_start);
+        state.swap_fee_ramp_end = i64::from_le_bytes(*swap_fee_ramp_end);
+        state.crank_fee_bps = u64::from_le_bytes(*crank_fee_bps);
+        state.crank_fee_cap_lamports = u64::from_le_bytes(*crank_fee_cap_lamports);
+        state.crank_fee_cap_tokens = u64::from_le_bytes(*crank_fee_cap_tokens);
+        state.central_exposure_cap_lamports = u64::from_le_bytes(*central_exposure_cap_lamports);
+        state.commit_reveal_window_slots = u64::from_le_bytes(*commit_reveal_window_slots);
+        state.central_liquidity_lock = central_liquidity_lock[0];
+        state.disabled_instructions_high = u128::from_le_bytes(*disabled_instructions_high);
+        state.pending_disabled_instructions_high = u128::from_le_bytes(*pending_disabled_instructions_high);
+
+        Ok(state)
+    }
+
+    // Manual serialization
+    pub fn pack(&self, dst: &mut [u8]) -> Result<(), ProgramError> {
+        if dst.len() < ProgramState::LEN {
+            msg!("Destination buffer too small for ProgramState");
+            return Err(ProgramError::InvalidAccountData);
+        }
+
+        let dst_array = array_mut_ref![dst, 0, ProgramState::LEN];
+        let (
+            admin_dst,
+            yot_mint_dst,
+            yos_mint_dst,
+            lp_contribution_rate_dst,
+            admin_fee_rate_dst,
+            yos_cashback_rate_dst,
+            swap_fee_rate_dst,
+            referral_rate_dst,
+            liquidity_wallet_dst,
+            liquidity_threshold_dst,
+            recovery_admin_dst,
+            emission_mode_dst,
+            weekly_emission_budget_dst,
+            allowlist_enabled_dst,
+            oracle_max_staleness_slots_dst,
+            oracle_max_confidence_bps_dst,
+            oracle_fallback_dst,
+            cashback_mint_selector_dst,
+            cashback_treasury_dst,
+            sol_fee_bps_dst,
+            destination_policy_dst,
+            max_price_impact_bps_dst,
+            central_cap_policy_dst,
+            state_bump_dst,
+            authority_bump_dst,
+            rebate_bps_dst,
+            ve_fee_tier_weight_dst,
+            ve_fee_discount_bps_dst,
+            dust_threshold_dst,
+            dust_bounty_lamports_dst,
+            liquidity_price_oracle_dst,
+            max_liquidity_deviation_bps_dst,
+            sol_pool_dst,
+            yot_pool_dst,
+            sol_yot_lp_mint_dst,
+            lp_farm_program_dst,
+            swap_hook_program_dst,
+            pending_swap_hook_dst,
+            swap_hook_effective_at_dst,
+            swap_hook_policy_dst,
+            min_swap_amount_lamports_dst,
+            min_swap_amount_tokens_dst,
+            liquidity_policy_dst,
+            cashback_base_dst,
+            referral_bonus_yos_dst,
+            inactivity_periods_dst,
+            liquidity_pool_selection_dst,
+            shutdown_recipient_dst,
+            shutdown_effective_at_dst,
+            shut_down_dst,
+            disabled_instructions_dst,
+            pending_disabled_instructions_dst,
+            instruction_flags_effective_at_dst,
+            scheduled_emission_dst,
+            yot_usd_oracle_dst,
+            yos_usd_oracle_dst,
+            operator_dst,
+            pauser_dst,
+            treasurer_dst,
+            auto_add_liquidity_dst,
+            market_maker_dst,
+            lp_contributions_dst,
+            referral_epoch_bonus_yos_dst,
+            insurance_fee_bps_dst,
+            cashback_rate_min_dst,
+            cashback_rate_max_dst,
+            cashback_target_price_dst,
+            bridge_operator_dst,
+            sell_contribution_side_dst,
+            config_version_dst,
+            lp_rate_ramp_target_dst,
+            lp_rate_ramp_start_dst,
+            lp_rate_ramp_end_dst,
+            swap_fee_ramp_target_dst,
+            swap_fee_ramp_start_dst,
+            swap_fee_ramp_end_dst,
+            crank_fee_bps_dst,
+            crank_fee_cap_lamports_dst,
+            crank_fee_cap_tokens_dst,
+            central_exposure_cap_lamports_dst,
+            commit_reveal_window_slots_dst,
+            central_liquidity_lock_dst,
+            disabled_instructions_high_dst,
+            pending_disabled_instructions_high_dst,
+        ) = mut_array_refs![dst_array, 32, 32, 32, 8, 8, 8, 8, 8, 32, 8, 32, 1, 8, 1, 8, 8, 1, 1, 32, 8, 1, 8, 1, 1, 1, 8, 8, 8, 8, 8, 32, 8, 32, 32, 32, 32, 32, 32, 8, 1, 8, 8, 1, 1, 8, 1, 1, 32, 8, 1, 16, 16, 8, 1, 32, 32, 32, 32, 32, 1, 32, 1, 8, 8, 8, 8, 8, 32, 1, 8, 8, 8, 8, 8, 8, 8, 8, 8, 8, 8, 8, 1, 16, 16];
+
+        admin_dst.copy_from_slice(self.admin.as_ref());
+        yot_mint_dst.copy_from_slice(self.yot_mint.as_ref());
+        yos_mint_dst.copy_from_slice(self.yos_mint.as_ref());
+        *lp_contribution_rate_dst = self.lp_contribution_rate.to_le_bytes();
+        *admin_fee_rate_dst = self.admin_fee_rate.to_le_bytes();
+        *yos_cashback_rate_dst = self.yos_cashback_rate.to_le_bytes();
+        *swap_fee_rate_dst = self.swap_fee_rate.to_le_bytes();
+        *referral_rate_dst = self.referral_rate.to_le_bytes();
+        liquidity_wallet_dst.copy_from_slice(self.liquidity_wallet.as_ref());
+        *liquidity_threshold_dst = self.liquidity_threshold.to_le_bytes();
+        recovery_admin_dst.copy_from_slice(self.recovery_admin.as_ref());
+        emission_mode_dst[0] = self.emission_mode;
+        *weekly_emission_budget_dst = self.weekly_emission_budget.to_le_bytes();
+        allowlist_enabled_dst[0] = self.allowlist_enabled;
+        *oracle_max_staleness_slots_dst = self.oracle_max_staleness_slots.to_le_bytes();
+        *oracle_max_confidence_bps_dst = self.oracle_max_confidence_bps.to_le_bytes();
+        oracle_fallback_dst[0] = self.oracle_fallback;
+        cashback_mint_selector_dst[0] = self.cashback_mint_selector;
+        cashback_treasury_dst.copy_from_slice(self.cashback_treasury.as_ref());
+        *sol_fee_bps_dst = self.sol_fee_bps.to_le_bytes();
+        destination_policy_dst[0] = self.destination_policy;
+        *max_price_impact_bps_dst = self.max_price_impact_bps.to_le_bytes();
+        central_cap_policy_dst[0] = self.central_cap_policy;
+        state_bump_dst[0] = self.state_bump;
+        authority_bump_dst[0] = self.authority_bump;
+        *rebate_bps_dst = self.rebate_bps.to_le_bytes();
+        *ve_fee_tier_weight_dst = self.ve_fee_tier_weight.to_le_bytes();
+        *ve_fee_discount_bps_dst = self.ve_fee_discount_bps.to_le_bytes();
+        *dust_threshold_dst = self.dust_threshold.to_le_bytes();
+        *dust_bounty_lamports_dst = self.dust_bounty_lamports.to_le_bytes();
+        liquidity_price_oracle_dst.copy_from_slice(self.liquidity_price_oracle.as_ref());
+        *max_liquidity_deviation_bps_dst = self.max_liquidity_deviation_bps.to_le_bytes();
+        sol_pool_dst.copy_from_slice(self.sol_pool.as_ref());
+        yot_pool_dst.copy_from_slice(self.yot_pool.as_ref());
+        sol_yot_lp_mint_dst.copy_from_slice(self.sol_yot_lp_mint.as_ref());
+        lp_farm_program_dst.copy_from_slice(self.lp_farm_program.as_ref());
+        swap_hook_program_dst.copy_from_slice(self.swap_hook_program.as_ref());
+        pending_swap_hook_dst.copy_from_slice(self.pending_swap_hook.as_ref());
+        *swap_hook_effective_at_dst = self.swap_hook_effective_at.to_le_bytes();
+        swap_hook_policy_dst[0] = self.swap_hook_policy;
+        *min_swap_amount_lamports_dst = self.min_swap_amount_lamports.to_le_bytes();
+        *min_swap_amount_tokens_dst = self.min_swap_amount_tokens.to_le_bytes();
+        liquidity_policy_dst[0] = self.liquidity_policy;
+        cashback_base_dst[0] = self.cashback_base;
+        *referral_bonus_yos_dst = self.referral_bonus_yos.to_le_bytes();
+        inactivity_periods_dst[0] = self.inactivity_periods;
+        liquidity_pool_selection_dst[0] = self.liquidity_pool_selection;
+        shutdown_recipient_dst.copy_from_slice(self.shutdown_recipient.as_ref());
+        *shutdown_effective_at_dst = self.shutdown_effective_at.to_le_bytes();
+        shut_down_dst[0] = self.shut_down;
+        *disabled_instructions_dst = self.disabled_instructions.to_le_bytes();
+        *pending_disabled_instructions_dst = self.pending_disabled_instructions.to_le_bytes();
+        *instruction_flags_effective_at_dst = self.instruction_flags_effective_at.to_le_bytes();
+        scheduled_emission_dst[0] = self.scheduled_emission;
+        yot_usd_oracle_dst.copy_from_slice(self.yot_usd_oracle.as_ref());
+        yos_usd_oracle_dst.copy_from_slice(self.yos_usd_oracle.as_ref());
+        operator_dst.copy_from_slice(self.operator.as_ref());
+        pauser_dst.copy_from_slice(self.pauser.as_ref());
+        treasurer_dst.copy_from_slice(self.treasurer.as_ref());
+        auto_add_liquidity_dst[0] = self.auto_add_liquidity;
+        market_maker_dst.copy_from_slice(self.market_maker.as_ref());
+        lp_contributions_dst[0] = self.lp_contributions;
+        *referral_epoch_bonus_yos_dst = self.referral_epoch_bonus_yos.to_le_bytes();
+        *insurance_fee_bps_dst = self.insurance_fee_bps.to_le_bytes();
+        *cashback_rate_min_dst = self.cashback_rate_min.to_le_bytes();
+        *cashback_rate_max_dst = self.cashback_rate_max.to_le_bytes();
+        *cashback_target_price_dst = self.cashback_target_price.to_le_bytes();
+        bridge_operator_dst.copy_from_slice(self.bridge_operator.as_ref());
+        sell_contribution_side_dst[0] = self.sell_contribution_side;
+        *config_version_dst = self.config_version.to_le_bytes();
+        *lp_rate_ramp_target_dst = self.lp_rate_ramp_target.to_le_bytes();
+        *lp_rate_ramp_start_dst = self.lp_rate_ramp_start.to_le_bytes();
+        *lp_rate_ramp_end_dst = self.lp_rate_ramp_end.to_le_bytes();
+        *swap_fee_ramp_target_dst = self.swap_fee_ramp_target.to_le_bytes();
+        *swap_fee_ramp_start_dst = self.swap_fee_ramp_start.to_le_bytes();
+        *swap_fee_ramp_end_dst = self.swap_fee_ramp_end.to_le_bytes();
+        *crank_fee_bps_dst = self.crank_fee_bps.to_le_bytes();
+        *crank_fee_cap_lamports_dst = self.crank_fee_cap_lamports.to_le_bytes();
+        *crank_fee_cap_tokens_dst = self.crank_fee_cap_tokens.to_le_bytes();
+        *central_exposure_cap_lamports_dst = self.central_exposure_cap_lamports.to_le_bytes();
+        *commit_reveal_window_slots_dst = self.commit_reveal_window_slots.to_le_bytes();
+        central_liquidity_lock_dst[0] = self.central_liquidity_lock;
+        *disabled_instructions_high_dst = self.disabled_instructions_high.to_le_bytes();
+        *pending_disabled_instructions_high_dst = self.pending_disabled_instructions_high.to_le_bytes();
+
+        Ok(())
+    }
+}
+
+// Staking program state, stored at [b"program_state"] of the staking program
+pub struct StakingProgramState {
+    pub protocol_version: u8,          // Layout version (0 = unversioned first-deployment layout)
+    pub admin: Pubkey,                 // Admin that can update parameters
+    pub yot_mint: Pubkey,              // Staked YOT mint
+    pub yos_mint: Pubkey,              // YOS reward mint
+    pub stake_rate_per_second: u64,    // Staking rate in basis points (1/100 of 1%)
+    pub harvest_threshold: u64,        // Minimum YOS for harvest
+}
+
+impl StakingProgramState {
+    // Unversioned layout of the first staking deployments
+    pub const LEN_V0: usize = 32 + 32 + 32 + 8 + 8; // 3 pubkeys + 2 u64s
+    // Current layout, led by the protocol version
+    pub const LEN: usize = 1 + Self::LEN_V0; // u8 + 3 pubkeys + 2 u64s
+
+    // Protocol version written by Initialize
+    pub const PROTOCOL_VERSION: u8 = 1;
+
+    pub fn unpack(data: &[u8]) -> Result<Self, ProgramError> {
+        // No staking layout is as long as the oldest swap layout, so this is never misread swap state
+        if data.len() >= ProgramState::LEN_V1 {
+            msg!("ERROR: {} bytes is a multi-hub swap ProgramState, not staking state", data.len());
+            return Err(ProgramError::InvalidAccountData);
+        }
+
+        if data.len() == Self::LEN_V0 {
+            msg!("Staking state has no protocol version (first deployment format)");
+            return Ok(Self::unpack_fields(0, array_ref![data, 0, StakingProgramState::LEN_V0]));
+        }
+
+        if data.len() < Self::LEN {
+            msg!("ERROR: Staking state too short: {} bytes", data.len());
+            return Err(ProgramError::InvalidAccountData);
+        }
+
+        if data[0] > Self::PROTOCOL_VERSION {
+            msg!("ERROR: Unknown staking protocol version {}", data[0]);
+            return Err(ProgramError::InvalidAccountData);
+        }
+
+        Ok(Self::unpack_fields(data[0], array_ref![data, 1, StakingProgramState::LEN_V0]))
+    }
+
+    // Writes the current layout, or the unversioned one into a first-deployment account
+    pub fn pack(&self, dst: &mut [u8]) -> Result<(), ProgramError> {
+        if dst.len() == Self::LEN_V0 {
+            self.pack_fields(array_mut_ref![dst, 0, StakingProgramState::LEN_V0]);
+            return Ok(());
+        }
+
+        if dst.len() < Self::LEN {
+            msg!("Destination buffer too small for StakingProgramState");
+            return Err(ProgramError::InvalidAccountData);
+        }
+
+        dst[0] = self.protocol_version;
+        self.pack_fields(array_mut_ref![dst, 1, StakingProgramState::LEN_V0]);
+        Ok(())
+    }
+
+    fn unpack_fields(protocol_version: u8, data: &[u8; StakingProgramState::LEN_V0]) -> Self {
+        let (admin, yot_mint, yos_mint, stake_rate_per_second, harvest_threshold) = array_refs![data, 32, 32, 32, 8, 8];
+        Self {
+            protocol_version,
+            admin: Pubkey::new_from_array(*admin),
+            yot_mint: Pubkey::new_from_array(*yot_mint),
+            yos_mint: Pubkey::new_from_array(*yos_mint),
+            stake_rate_per_second: u64::from_le_bytes(*stake_rate_per_second),
+            harvest_threshold: u64::from_le_bytes(*harvest_threshold),
+        }
+    }
+
+    fn pack_fields(&self, dst: &mut [u8; StakingProgramState::LEN_V0]) {
+        let (admin_dst, yot_mint_dst, yos_mint_dst, stake_rate_per_second_dst, harvest_threshold_dst) =
+            mut_array_refs![dst, 32, 32, 32, 8, 8];
+        admin_dst.copy_from_slice(self.admin.as_ref());
+        yot_mint_dst.copy_from_slice(self.yot_mint.as_ref());
+        yos_mint_dst.copy_from_slice(self.yos_mint.as_ref());
+        *stake_rate_per_second_dst = self.stake_rate_per_second.to_le_bytes();
+        *harvest_threshold_dst = self.harvest_threshold.to_le_bytes();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn staking_state_reads_and_keeps_unversioned_layout() {
+        let mut data = [0u8; StakingProgramState::LEN_V0];
+        data[..32].copy_from_slice(&[7; 32]);
+        data[96..104].copy_from_slice(&500u64.to_le_bytes());
+
+        let mut state = StakingProgramState::unpack(&data).unwrap();
+        assert_eq!(state.protocol_version, 0);
+        assert_eq!(state.admin, Pubkey::new_from_array([7; 32]));
+        assert_eq!(state.stake_rate_per_second, 500);
+
+        state.harvest_threshold = 9;
+        state.pack(&mut data).unwrap();
+        assert_eq!(&data[104..], &9u64.to_le_bytes());
+
+        let mut versioned = [0u8; StakingProgramState::LEN];
+        state.protocol_version = StakingProgramState::PROTOCOL_VERSION;
+        state.pack(&mut versioned).unwrap();
+        assert_eq!(versioned[0], StakingProgramState::PROTOCOL_VERSION);
+        assert_eq!(&versioned[1..], &data[..]);
+    }
+
+    #[test]
+    fn staking_state_rejects_swap_state() {
+        assert!(StakingProgramState::unpack(&[0u8; ProgramState::LEN_V1]).is_err());
+        assert!(StakingProgramState::unpack(&[0u8; ProgramState::LEN]).is_err());
+    }
+
+    #[test]
+    fn changed_fields_name_each_parameter_with_old_and_new_bytes() {
+        assert_eq!(ProgramState::FIELDS.iter().map(|(_, size)| size).sum::<usize>(), ProgramState::LEN);
+
+        let mut state = ProgramState::unpack(&[0u8; ProgramState::LEN]).unwrap();
+        state.swap_fee_rate = 1;
+        let mut before = [0u8; ProgramState::LEN];
+        state.pack(&mut before).unwrap();
+
+        state.swap_fee_rate = 3;
+        state.config_version = 8;
+        let mut after = [0u8; ProgramState::LEN];
+        state.pack(&mut after).unwrap();
+
+        let changes = ProgramState::changed_fields(&before, &after);
+        assert_eq!(changes.len(), 1);
+        assert_eq!(changes[0].0, "swap_fee_rate");
+        assert_eq!(changes[0].1, &1u64.to_le_bytes());
+        assert_eq!(changes[0].2, &3u64.to_le_bytes());
+        assert!(ProgramState::changed_fields(&after, &after).is_empty());
+    }
+
+    #[test]
+    fn state_bump_offset_matches_the_layout() {
+        let offset: usize = ProgramState::FIELDS.iter().take_while(|(name, _)| *name != "state_bump").map(|(_, size)| size).sum();
+        assert_eq!(offset, ProgramState::STATE_BUMP_OFFSET);
+
+        let mut state = ProgramState::unpack(&[0u8; ProgramState::LEN]).unwrap();
+        state.state_bump = 254;
+        let mut data = [0u8; ProgramState::LEN];
+        state.pack(&mut data).unwrap();
+        assert_eq!(data[ProgramState::STATE_BUMP_OFFSET], 254);
+    }
+
+    #[test]
+    fn unassigned_roles_fall_back_to_the_owner() {
+        let mut data = [0u8; ProgramState::LEN];
+        data[..32].copy_from_slice(&[7; 32]);
+        let owner = Pubkey::new_from_array([7; 32]);
+
+        let mut state = ProgramState::unpack(&data[..ProgramState::LEN_V2]).unwrap();
+        assert_eq!(state.role_holder(AdminRole::Pauser), owner);
+
+        state.treasurer = Pubkey::new_from_array([9; 32]);
+        state.pack(&mut data).unwrap();
+        let state = ProgramState::unpack(&data).unwrap();
+        assert_eq!(state.role_holder(AdminRole::Treasurer), Pubkey::new_from_array([9; 32]));
+        assert_eq!(state.role_holder(AdminRole::Operator), owner);
+        assert_eq!(state.role_holder(AdminRole::Owner), owner);
+    }
+
+    #[test]
+    fn ramps_move_linearly_from_the_stored_rate_to_the_target() {
+        let mut state = ProgramState::unpack(&[0u8; ProgramState::LEN]).unwrap();
+        state.lp_contribution_rate = 25;
+        assert_eq!(state.effective_lp_contribution_rate(1_000), 25);
+
+        state.lp_rate_ramp_target = 20;
+        state.lp_rate_ramp_start = 1_000;
+        state.lp_rate_ramp_end = 2_000;
+        assert_eq!(state.effective_lp_contribution_rate(500), 25);
+        assert_eq!(state.effective_lp_contribution_rate(1_200), 24);
+        assert_eq!(state.effective_lp_contribution_rate(1_500), 23); // 22.5 rounds toward the start
+        assert_eq!(state.effective_lp_contribution_rate(2_000), 20);
+        assert_eq!(state.effective_lp_contribution_rate(i64::MAX), 20);
+
+        state.swap_fee_ramp_target = 1;
+        state.swap_fee_ramp_start = 0;
+        state.swap_fee_ramp_end = 10;
+        assert_eq!(state.effective_swap_fee_rate(5), 0);
+        assert_eq!(state.effective_swap_fee_rate(10), 1);
+
+        let mut data = [0u8; ProgramState::LEN];
+        state.pack(&mut data).unwrap();
+        assert_eq!(ProgramState::unpack(&data).unwrap().effective_lp_contribution_rate(1_500), 23);
+        assert_eq!(ProgramState::unpack(&data[..ProgramState::LEN_V2]).unwrap().effective_lp_contribution_rate(1_500), 25);
+    }
+}
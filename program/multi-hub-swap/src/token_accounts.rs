@@ -0,0 +1,119 @@
+//! Per-instruction cache of unpacked SPL token accounts
+//!
+//! Swap handlers read the same reserves several times: the mint for a direction check,
+//! the balance for pricing and the price-impact cap, and again for the invariant check.
+//! The cache unpacks each account once and holds no borrow of its data afterwards, so
+//! a later CPI can write to the account. Call `refresh` once a CPI has moved tokens.
+
+use solana_program::{
+    account_info::AccountInfo,
+    program_error::ProgramError,
+    program_pack::Pack,
+    pubkey::Pubkey,
+};
+use spl_token::state::Account;
+
+/// Token accounts unpacked so far in the current instruction, keyed by address
+#[derive(Default)]
+pub struct TokenAccountCache {
+    entries: Vec<(Pubkey, Account)>,
+}
+
+impl TokenAccountCache {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Unpacked `account`, read from its data on first use only
+    pub fn get(&mut self, account: &AccountInfo) -> Result<&Account, ProgramError> {
+        match self.entries.iter().position(|(key, _)| key == account.key) {
+            Some(index) => Ok(&self.entries[index].1),
+            None => self.refresh(account),
+        }
+    }
+
+    /// Mint of `account`
+    pub fn mint(&mut self, account: &AccountInfo) -> Result<Pubkey, ProgramError> {
+        Ok(self.get(account)?.mint)
+    }
+
+    /// Balance of `account` as of its last unpack
+    pub fn balance(&mut self, account: &AccountInfo) -> Result<u64, ProgramError> {
+        Ok(self.get(account)?.amount)
+    }
+
+    /// Re-read `account` after a CPI has changed it
+    pub fn refresh(&mut self, account: &AccountInfo) -> Result<&Account, ProgramError> {
+        let unpacked = Account::unpack(&account.data.borrow())?;
+        let index = match self.entries.iter().position(|(key, _)| key == account.key) {
+            Some(index) => {
+                self.entries[index].1 = unpacked;
+                index
+            }
+            None => {
+                let index = self.entries.len();
+                self.entries.push((*account.key, unpacked));
+                index
+            }
+        };
+        Ok(&self.entries[index].1)
+    }
+
+    /// Fresh balance of `account`, for reads after a CPI
+    pub fn refreshed_balance(&mut self, account: &AccountInfo) -> Result<u64, ProgramError> {
+        Ok(self.refresh(account)?.amount)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use spl_token::state::AccountState;
+
+    fn packed(mint: Pubkey, amount: u64) -> Vec<u8> {
+        let mut data = vec![0u8; Account::LEN];
+        let account = Account {
+            mint,
+            owner: Pubkey::new_unique(),
+            amount,
+            state: AccountState::Initialized,
+            ..Account::default()
+        };
+        Account::pack(account, &mut data).unwrap();
+        data
+    }
+
+    #[test]
+    fn unpacks_once_until_refreshed() {
+        let key = Pubkey::new_unique();
+        let owner = spl_token::id();
+        let mint = Pubkey::new_unique();
+        let mut lamports = 0;
+        let mut data = packed(mint, 100);
+        let account = AccountInfo::new(&key, false, true, &mut lamports, &mut data, &owner, false, 0);
+
+        let mut cache = TokenAccountCache::new();
+        assert_eq!(cache.mint(&account).unwrap(), mint);
+        assert_eq!(cache.balance(&account).unwrap(), 100);
+
+        // A write the cache has not been told about stays invisible until refresh
+        account.data.borrow_mut().copy_from_slice(&packed(mint, 40));
+        assert_eq!(cache.balance(&account).unwrap(), 100);
+        assert_eq!(cache.refreshed_balance(&account).unwrap(), 40);
+        assert_eq!(cache.balance(&account).unwrap(), 40);
+        assert_eq!(cache.entries.len(), 1);
+    }
+
+    #[test]
+    fn rejects_non_token_data() {
+        let key = Pubkey::new_unique();
+        let owner = spl_token::id();
+        let mut lamports = 0;
+        let mut data = vec![0u8; 8];
+        let account = AccountInfo::new(&key, false, true, &mut lamports, &mut data, &owner, false, 0);
+
+        let mut cache = TokenAccountCache::new();
+        assert!(cache.balance(&account).is_err());
+        assert!(cache.entries.is_empty());
+    }
+}
@@ -0,0 +1,55 @@
+//! The central wallet exposure cap set by SetCentralExposureCap: once the central liquidity
+//! wallet holds the cap, swaps leave their liquidity contribution in the pool reserve
+
+#![cfg(feature = "processor-multi-hub-swap")]
+// clippy.toml lets #[test] functions unwrap; the async tests and their helpers may too
+#![allow(clippy::unwrap_used)]
+
+mod common;
+
+use common::*;
+use multi_hub_swap_core::calculate_swap_output;
+use multi_hub_swap_core::safe_math::percent_of;
+use solana_sdk::signature::Signer;
+
+const EXPOSURE_CAP: u64 = 1_000_000_000;
+
+/// Sell with the central wallet holding `central_wallet_lamports` under EXPOSURE_CAP and
+/// return the central wallet's and the SOL pool's balances after the sale
+async fn sell_with_central_balance(central_wallet_lamports: u64) -> (u64, u64) {
+    let mut sale = YotSale::new();
+    sale.program_state.central_exposure_cap_lamports = EXPOSURE_CAP;
+    sale.central_wallet_lamports = central_wallet_lamports;
+    let (mut banks_client, created_rent) = sale.run().await;
+
+    // The user is paid their share either way
+    let sol_out = calculate_swap_output(SALE_AMOUNT_IN, SALE_YOT_RESERVE, SALE_SOL_RESERVE).unwrap();
+    let user_share = percent_of(sol_out, 100 - SALE_LP_RATE);
+    assert_eq!(
+        banks_client.get_balance(sale.user.pubkey()).await.unwrap(),
+        SALE_WALLET_LAMPORTS.saturating_add(user_share).saturating_sub(created_rent),
+    );
+    let central_balance = banks_client.get_balance(sale.program_state.liquidity_wallet).await.unwrap();
+    let pool_balance = banks_client.get_balance(program_authority_address(&sale.program_id)).await.unwrap();
+    (central_balance, pool_balance)
+}
+
+#[tokio::test]
+async fn contribution_reaches_the_central_wallet_under_the_cap() {
+    let (central_balance, pool_balance) = sell_with_central_balance(EXPOSURE_CAP - 1).await;
+
+    let sol_out = calculate_swap_output(SALE_AMOUNT_IN, SALE_YOT_RESERVE, SALE_SOL_RESERVE).unwrap();
+    let contribution = sol_out * SALE_LP_RATE / 100;
+    assert_eq!(central_balance, EXPOSURE_CAP - 1 + contribution);
+    assert_eq!(pool_balance, SALE_SOL_RESERVE - sol_out * (100 - SALE_LP_RATE) / 100 - contribution);
+}
+
+#[tokio::test]
+async fn contribution_stays_in_the_pool_at_the_cap() {
+    let (central_balance, pool_balance) = sell_with_central_balance(EXPOSURE_CAP).await;
+
+    // Only the user's share leaves the pool
+    let sol_out = calculate_swap_output(SALE_AMOUNT_IN, SALE_YOT_RESERVE, SALE_SOL_RESERVE).unwrap();
+    assert_eq!(central_balance, EXPOSURE_CAP);
+    assert_eq!(pool_balance, SALE_SOL_RESERVE - sol_out * (100 - SALE_LP_RATE) / 100);
+}
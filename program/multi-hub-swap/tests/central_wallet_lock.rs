@@ -0,0 +1,117 @@
+//! The central liquidity wallet lock: AddLiquidityFromCentralWallet (discriminator 11) fails
+//! instead of moving the wallet while another operation holds it or has moved its epoch
+
+#![cfg(feature = "processor-multi-hub-swap")]
+// clippy.toml lets #[test] functions unwrap; the async tests and their helpers may too
+#![allow(clippy::unwrap_used)]
+
+mod common;
+
+use common::*;
+use multi_hub_swap::multi_hub_swap_complete::{CentralLiquidityState, MultiHubSwapError};
+use multi_hub_swap::state::ProgramState;
+use solana_program_test::ProgramTest;
+use solana_sdk::{
+    instruction::{AccountMeta, Instruction, InstructionError},
+    pubkey::Pubkey,
+    signature::{Keypair, Signer},
+    system_program, sysvar,
+    transaction::TransactionError,
+};
+
+const EPOCH: u64 = 7;
+
+/// A bank with the lock on and the central liquidity state at EPOCH, held when `locked`
+fn locked_wallet_test(program_id: &Pubkey, operator: &Keypair, locked: bool) -> (ProgramTest, ProgramState) {
+    let mut program_test = program_test(*program_id);
+    let mut program_state = default_program_state(program_id);
+    program_state.admin = operator.pubkey();
+    program_state.yot_mint = Pubkey::new_unique();
+    program_state.liquidity_wallet = Pubkey::new_unique();
+    program_state.central_liquidity_lock = 1;
+    add_program_state(&mut program_test, program_id, &program_state);
+    add_wallet(&mut program_test, operator.pubkey(), 1_000_000_000);
+
+    let (state_key, bump) = central_liquidity_state_address(program_id);
+    let mut data = vec![0u8; CentralLiquidityState::LEN];
+    CentralLiquidityState { epoch: EPOCH, locked: u8::from(locked), bump }.pack(&mut data).unwrap();
+    program_test.add_account(state_key, account_with_data(data, *program_id));
+    (program_test, program_state)
+}
+
+fn central_liquidity_state_address(program_id: &Pubkey) -> (Pubkey, u8) {
+    Pubkey::find_program_address(&[b"central_liquidity"], program_id)
+}
+
+/// AddLiquidityFromCentralWallet run by the operator, with the central liquidity state after
+/// the fixed accounts and `expected_epoch` when given
+fn add_liquidity_instruction(
+    program_id: &Pubkey,
+    program_state: &ProgramState,
+    operator: &Pubkey,
+    with_lock_state: bool,
+    expected_epoch: Option<u64>,
+) -> Instruction {
+    let authority = program_authority_address(program_id);
+    let mut accounts = vec![
+        AccountMeta::new(*operator, true),
+        AccountMeta::new_readonly(program_state_address(program_id), false),
+        AccountMeta::new_readonly(authority, false),
+        AccountMeta::new(authority, false),
+        AccountMeta::new(associated_token_address(&authority, &program_state.yot_mint), false),
+        AccountMeta::new(program_state.liquidity_wallet, false),
+        // Central YOT account, LP mint and LP account: the lock is taken before they are read
+        AccountMeta::new(Pubkey::new_unique(), false),
+        AccountMeta::new(Pubkey::new_unique(), false),
+        AccountMeta::new(Pubkey::new_unique(), false),
+        AccountMeta::new_readonly(system_program::id(), false),
+        AccountMeta::new_readonly(spl_token::id(), false),
+        AccountMeta::new_readonly(sysvar::rent::id(), false),
+    ];
+    if with_lock_state {
+        accounts.push(AccountMeta::new(central_liquidity_state_address(program_id).0, false));
+    }
+    let mut data = vec![11];
+    if let Some(epoch) = expected_epoch {
+        data.extend_from_slice(&epoch.to_le_bytes());
+    }
+    Instruction { program_id: *program_id, accounts, data }
+}
+
+/// Run `instruction` signed by the operator
+async fn run(program_test: ProgramTest, operator: &Keypair, instruction: Instruction) -> Result<(), TransactionError> {
+    let (mut banks_client, payer, _) = program_test.start().await;
+    let transaction = transaction(&mut banks_client, &payer, &[instruction], &[operator]).await;
+    banks_client.process_transaction(transaction).await.map_err(|error| error.unwrap())
+}
+
+#[tokio::test]
+async fn add_liquidity_is_refused_while_the_wallet_is_locked() {
+    let (program_id, operator) = (Pubkey::new_unique(), Keypair::new());
+    let (program_test, program_state) = locked_wallet_test(&program_id, &operator, true);
+
+    let instruction = add_liquidity_instruction(&program_id, &program_state, &operator.pubkey(), true, None);
+    let result = run(program_test, &operator, instruction).await;
+    assert_eq!(result, Err(program_error(MultiHubSwapError::CentralWalletLocked)));
+}
+
+#[tokio::test]
+async fn add_liquidity_is_refused_once_the_epoch_has_moved() {
+    let (program_id, operator) = (Pubkey::new_unique(), Keypair::new());
+    let (program_test, program_state) = locked_wallet_test(&program_id, &operator, false);
+
+    // The caller read the epoch before the last operation on the wallet
+    let instruction = add_liquidity_instruction(&program_id, &program_state, &operator.pubkey(), true, Some(EPOCH - 1));
+    let result = run(program_test, &operator, instruction).await;
+    assert_eq!(result, Err(program_error(MultiHubSwapError::CentralWalletEpochChanged)));
+}
+
+#[tokio::test]
+async fn add_liquidity_needs_the_lock_state_once_the_lock_is_on() {
+    let (program_id, operator) = (Pubkey::new_unique(), Keypair::new());
+    let (program_test, program_state) = locked_wallet_test(&program_id, &operator, false);
+
+    let instruction = add_liquidity_instruction(&program_id, &program_state, &operator.pubkey(), false, Some(EPOCH));
+    let result = run(program_test, &operator, instruction).await;
+    assert_eq!(result, Err(TransactionError::InstructionError(0, InstructionError::NotEnoughAccountKeys)));
+}
@@ -0,0 +1,132 @@
+//! CommitSwap (discriminator 133) and RevealSwap (discriminator 134): a reveal only runs the
+//! swap it committed to, and only within the commit-reveal window
+
+#![cfg(feature = "processor-multi-hub-swap")]
+// clippy.toml lets #[test] functions unwrap; the async tests and their helpers may too
+#![allow(clippy::unwrap_used)]
+
+mod common;
+
+use common::*;
+use multi_hub_swap::multi_hub_swap_complete::{swap_commitment, MultiHubSwapError, SwapCommitment};
+use solana_program_test::ProgramTestContext;
+use solana_sdk::{
+    instruction::{AccountMeta, Instruction, InstructionError},
+    pubkey::Pubkey,
+    signature::{Keypair, Signer},
+    system_program,
+    transaction::TransactionError,
+};
+
+const WINDOW_SLOTS: u64 = 20;
+const AMOUNT_IN: u64 = 1_000_000;
+const MIN_AMOUNT_OUT: u64 = 900_000;
+const SALT: [u8; 32] = [7u8; 32];
+
+/// A user with a bank where commit-reveal swaps are on
+struct Committer {
+    program_id: Pubkey,
+    user: Keypair,
+    commitment: Pubkey,
+}
+
+impl Committer {
+    async fn start() -> (Self, ProgramTestContext) {
+        let program_id = Pubkey::new_unique();
+        let user = Keypair::new();
+        let mut program_test = program_test(program_id);
+        let mut program_state = default_program_state(&program_id);
+        program_state.commit_reveal_window_slots = WINDOW_SLOTS;
+        add_program_state(&mut program_test, &program_id, &program_state);
+        add_wallet(&mut program_test, user.pubkey(), 1_000_000_000);
+        let commitment = Pubkey::find_program_address(&[b"swap_commit", user.pubkey().as_ref()], &program_id).0;
+        (Self { program_id, user, commitment }, program_test.start_with_context().await)
+    }
+
+    /// CommitSwap of a SOL to YOT swap of AMOUNT_IN for at least MIN_AMOUNT_OUT
+    fn commit_instruction(&self) -> Instruction {
+        let hash = swap_commitment(&self.program_id, &self.user.pubkey(), SwapCommitment::SOL_TO_YOT, AMOUNT_IN, MIN_AMOUNT_OUT, &SALT);
+        let mut data = vec![133];
+        data.extend_from_slice(&hash);
+        Instruction {
+            program_id: self.program_id,
+            accounts: vec![
+                AccountMeta::new(self.user.pubkey(), true),
+                AccountMeta::new(self.commitment, false),
+                AccountMeta::new_readonly(program_state_address(&self.program_id), false),
+                AccountMeta::new_readonly(system_program::id(), false),
+            ],
+            data,
+        }
+    }
+
+    /// RevealSwap of a SOL to YOT swap; the swap's own accounts stop at the user and the
+    /// program state, since every test here is refused before the swap runs
+    fn reveal_instruction(&self, amount_in: u64, min_amount_out: u64, salt: &[u8; 32]) -> Instruction {
+        let mut data = vec![134, SwapCommitment::SOL_TO_YOT];
+        data.extend_from_slice(&amount_in.to_le_bytes());
+        data.extend_from_slice(&min_amount_out.to_le_bytes());
+        data.extend_from_slice(salt);
+        Instruction {
+            program_id: self.program_id,
+            accounts: vec![
+                AccountMeta::new(self.commitment, false),
+                AccountMeta::new(self.user.pubkey(), true),
+                AccountMeta::new_readonly(program_state_address(&self.program_id), false),
+            ],
+            data,
+        }
+    }
+
+    async fn run(&self, context: &mut ProgramTestContext, instructions: &[Instruction]) -> Result<(), TransactionError> {
+        let transaction = transaction(&mut context.banks_client, &context.payer, instructions, &[&self.user]).await;
+        context.banks_client.process_transaction(transaction).await.map_err(|error| error.unwrap())
+    }
+
+    /// The slot the pending commitment was recorded in
+    async fn committed_slot(&self, context: &mut ProgramTestContext) -> u64 {
+        let account = context.banks_client.get_account(self.commitment).await.unwrap().unwrap();
+        SwapCommitment::unpack(&account.data).unwrap().committed_slot
+    }
+}
+
+#[tokio::test]
+async fn reveal_that_does_not_match_the_commitment_is_rejected() {
+    let (committer, mut context) = Committer::start().await;
+    committer.run(&mut context, &[committer.commit_instruction()]).await.unwrap();
+    let committed_slot = committer.committed_slot(&mut context).await;
+    context.warp_to_slot(committed_slot + 1).unwrap();
+
+    for reveal in [
+        committer.reveal_instruction(AMOUNT_IN + 1, MIN_AMOUNT_OUT, &SALT),
+        committer.reveal_instruction(AMOUNT_IN, MIN_AMOUNT_OUT - 1, &SALT),
+        committer.reveal_instruction(AMOUNT_IN, MIN_AMOUNT_OUT, &[8u8; 32]),
+    ] {
+        let result = committer.run(&mut context, &[reveal]).await;
+        assert_eq!(result, Err(program_error(MultiHubSwapError::SwapCommitmentMismatch)));
+    }
+    // The commitment stays pending for the matching reveal
+    assert_eq!(committer.committed_slot(&mut context).await, committed_slot);
+}
+
+#[tokio::test]
+async fn reveal_in_the_commit_slot_is_rejected() {
+    let (committer, mut context) = Committer::start().await;
+
+    let reveal = committer.reveal_instruction(AMOUNT_IN, MIN_AMOUNT_OUT, &SALT);
+    let result = committer.run(&mut context, &[committer.commit_instruction(), reveal]).await;
+    let error = InstructionError::Custom(MultiHubSwapError::SwapRevealOutsideWindow as u32);
+    assert_eq!(result, Err(TransactionError::InstructionError(1, error)));
+}
+
+#[tokio::test]
+async fn reveal_after_the_window_has_expired_is_rejected() {
+    let (committer, mut context) = Committer::start().await;
+    committer.run(&mut context, &[committer.commit_instruction()]).await.unwrap();
+    let committed_slot = committer.committed_slot(&mut context).await;
+    context.warp_to_slot(committed_slot + WINDOW_SLOTS + 1).unwrap();
+
+    let result = committer.run(&mut context, &[committer.reveal_instruction(AMOUNT_IN, MIN_AMOUNT_OUT, &SALT)]).await;
+    assert_eq!(result, Err(program_error(MultiHubSwapError::SwapRevealOutsideWindow)));
+    assert!(context.banks_client.get_account(committer.commitment).await.unwrap().is_some());
+}
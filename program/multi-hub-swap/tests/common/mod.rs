@@ -0,0 +1,346 @@
+//! Shared setup for the program-tests
+//!
+//! Accounts are written straight into the test bank in their packed layouts, so each test
+//! starts from the state it needs instead of replaying the admin instructions that build it.
+//! Under `cargo test-sbf` the program runs from the built .so and is compute metered;
+//! under `cargo test` it runs natively.
+
+#![allow(dead_code)]
+
+use multi_hub_swap::constants::ASSOCIATED_TOKEN_PROGRAM_ID;
+use multi_hub_swap::multi_hub_swap_complete::{
+    process_instruction, GlobalContributionState, LiquidityContribution, LiquidityPool, MultiHubSwapError, PoolRegistry,
+    RegisteredPool, UserHistory,
+};
+use multi_hub_swap::state::ProgramState;
+use solana_program::program_pack::Pack;
+use solana_program_test::{processor, BanksClient, ProgramTest};
+use solana_sdk::{
+    account::Account,
+    instruction::{AccountMeta, Instruction, InstructionError},
+    pubkey::Pubkey,
+    rent::Rent,
+    signature::{Keypair, Signer},
+    system_program, sysvar,
+    transaction::{Transaction, TransactionError},
+};
+
+/// A program-test bank running this program under `program_id`
+pub fn program_test(program_id: Pubkey) -> ProgramTest {
+    ProgramTest::new("multi_hub_swap", program_id, processor!(process_instruction))
+}
+
+pub fn program_state_address(program_id: &Pubkey) -> Pubkey {
+    Pubkey::find_program_address(&[b"state"], program_id).0
+}
+
+pub fn program_authority_address(program_id: &Pubkey) -> Pubkey {
+    Pubkey::find_program_address(&[b"authority"], program_id).0
+}
+
+pub fn pool_registry_address(program_id: &Pubkey) -> Pubkey {
+    Pubkey::find_program_address(&[b"pool_registry"], program_id).0
+}
+
+/// A zeroed program state with its PDA bumps recorded, as left by Initialize
+pub fn default_program_state(program_id: &Pubkey) -> ProgramState {
+    let mut program_state = ProgramState::unpack(&[0u8; ProgramState::LEN]).unwrap();
+    program_state.state_bump = Pubkey::find_program_address(&[b"state"], program_id).1;
+    program_state.authority_bump = Pubkey::find_program_address(&[b"authority"], program_id).1;
+    program_state
+}
+
+/// Rent-exempt account holding `data`
+pub fn account_with_data(data: Vec<u8>, owner: Pubkey) -> Account {
+    Account {
+        lamports: Rent::default().minimum_balance(data.len()),
+        data,
+        owner,
+        executable: false,
+        rent_epoch: 0,
+    }
+}
+
+pub fn add_program_state(program_test: &mut ProgramTest, program_id: &Pubkey, program_state: &ProgramState) {
+    let mut data = vec![0u8; ProgramState::LEN];
+    program_state.pack(&mut data).unwrap();
+    program_test.add_account(program_state_address(program_id), account_with_data(data, *program_id));
+}
+
+pub fn add_mint(program_test: &mut ProgramTest, mint: Pubkey, authority: Pubkey, supply: u64) {
+    let mut data = vec![0u8; spl_token::state::Mint::LEN];
+    spl_token::state::Mint {
+        mint_authority: Some(authority).into(),
+        supply,
+        decimals: 9,
+        is_initialized: true,
+        freeze_authority: None.into(),
+    }
+    .pack_into_slice(&mut data);
+    program_test.add_account(mint, account_with_data(data, spl_token::id()));
+}
+
+pub fn add_token_account(program_test: &mut ProgramTest, key: Pubkey, mint: Pubkey, owner: Pubkey, amount: u64) {
+    let mut data = vec![0u8; spl_token::state::Account::LEN];
+    spl_token::state::Account {
+        mint,
+        owner,
+        amount,
+        state: spl_token::state::AccountState::Initialized,
+        ..Default::default()
+    }
+    .pack_into_slice(&mut data);
+    program_test.add_account(key, account_with_data(data, spl_token::id()));
+}
+
+/// One registered pool, its reserves and the mints they hold
+pub struct TestPool {
+    pub pool: Pubkey,
+    pub reserve_a: Pubkey,
+    pub reserve_b: Pubkey,
+    pub mint_a: Pubkey,
+    pub mint_b: Pubkey,
+}
+
+/// Register a pool for every adjacent pair of `mints`, each reserve holding `reserve` tokens
+/// Pool `i` trades `mints[i]` for `mints[i + 1]` and sits at registry index `i`. The mints
+/// are created too, with the program authority as mint authority
+pub fn add_pool_chain(program_test: &mut ProgramTest, program_id: &Pubkey, mints: &[Pubkey], reserve: u64) -> Vec<TestPool> {
+    let authority = program_authority_address(program_id);
+    for mint in mints {
+        add_mint(program_test, *mint, authority, reserve.saturating_mul(2));
+    }
+    let mut registry = PoolRegistry { pools: Vec::new() };
+    let mut pools = Vec::new();
+    for pair in mints.windows(2) {
+        let test_pool = TestPool {
+            pool: Pubkey::new_unique(),
+            reserve_a: Pubkey::new_unique(),
+            reserve_b: Pubkey::new_unique(),
+            mint_a: pair[0],
+            mint_b: pair[1],
+        };
+        let mut pool = LiquidityPool::unpack(&[0u8; LiquidityPool::LEN]).unwrap();
+        pool.token_a_mint = pair[0];
+        pool.token_b_mint = pair[1];
+        pool.reserve_a = test_pool.reserve_a;
+        pool.reserve_b = test_pool.reserve_b;
+        let mut data = vec![0u8; LiquidityPool::LEN];
+        pool.pack(&mut data).unwrap();
+        program_test.add_account(test_pool.pool, account_with_data(data, *program_id));
+        add_token_account(program_test, test_pool.reserve_a, pair[0], authority, reserve);
+        add_token_account(program_test, test_pool.reserve_b, pair[1], authority, reserve);
+        registry.pools.push(RegisteredPool { pool: test_pool.pool, token_a_mint: pair[0], token_b_mint: pair[1], paused: false });
+        pools.push(test_pool);
+    }
+    let mut data = vec![0u8; PoolRegistry::LEN];
+    registry.pack(&mut data).unwrap();
+    program_test.add_account(pool_registry_address(program_id), account_with_data(data, *program_id));
+    pools
+}
+
+/// PoolSwap (discriminator 18) of `amount_in` from `user_source` through `pool`
+/// The pool's mints follow the fixed accounts for the transfers to find
+pub fn pool_swap_instruction(
+    program_id: &Pubkey,
+    user: &Pubkey,
+    pool: &TestPool,
+    user_source: &Pubkey,
+    user_destination: &Pubkey,
+    amount_in: u64,
+    min_amount_out: u64,
+) -> Instruction {
+    let mut data = vec![18];
+    data.extend_from_slice(&amount_in.to_le_bytes());
+    data.extend_from_slice(&min_amount_out.to_le_bytes());
+    Instruction {
+        program_id: *program_id,
+        accounts: vec![
+            AccountMeta::new(*user, true),
+            AccountMeta::new_readonly(program_state_address(program_id), false),
+            AccountMeta::new_readonly(program_authority_address(program_id), false),
+            AccountMeta::new_readonly(pool_registry_address(program_id), false),
+            AccountMeta::new(pool.pool, false),
+            AccountMeta::new(pool.reserve_a, false),
+            AccountMeta::new(pool.reserve_b, false),
+            AccountMeta::new(*user_source, false),
+            AccountMeta::new(*user_destination, false),
+            AccountMeta::new_readonly(spl_token::id(), false),
+            AccountMeta::new_readonly(pool.mint_a, false),
+            AccountMeta::new_readonly(pool.mint_b, false),
+        ],
+        data,
+    }
+}
+
+/// RoutedPoolSwap (discriminator 25) through `pools` in order
+pub fn routed_pool_swap_instruction(
+    program_id: &Pubkey,
+    user: &Pubkey,
+    pools: &[TestPool],
+    user_source: &Pubkey,
+    user_destination: &Pubkey,
+    amount_in: u64,
+    min_amount_out: u64,
+) -> Instruction {
+    let mut data = vec![25];
+    data.extend_from_slice(&amount_in.to_le_bytes());
+    data.extend_from_slice(&min_amount_out.to_le_bytes());
+    data.push(pools.len() as u8);
+    data.extend((0..pools.len()).map(|index| index as u8));
+    let mut accounts = vec![
+        AccountMeta::new(*user, true),
+        AccountMeta::new_readonly(program_state_address(program_id), false),
+        AccountMeta::new_readonly(program_authority_address(program_id), false),
+        AccountMeta::new_readonly(pool_registry_address(program_id), false),
+        AccountMeta::new(*user_source, false),
+        AccountMeta::new(*user_destination, false),
+        AccountMeta::new_readonly(spl_token::id(), false),
+    ];
+    for pool in pools {
+        accounts.push(AccountMeta::new(pool.pool, false));
+        accounts.push(AccountMeta::new(pool.reserve_a, false));
+        accounts.push(AccountMeta::new(pool.reserve_b, false));
+    }
+    // Each mint once, for the transfers between hops to find
+    let mut mints: Vec<Pubkey> = pools.iter().flat_map(|pool| [pool.mint_a, pool.mint_b]).collect();
+    mints.dedup();
+    accounts.extend(mints.into_iter().map(|mint| AccountMeta::new_readonly(mint, false)));
+    Instruction { program_id: *program_id, accounts, data }
+}
+
+/// Sign `instructions` with the payer and any `signers`
+pub async fn transaction(
+    banks_client: &mut BanksClient,
+    payer: &Keypair,
+    instructions: &[Instruction],
+    signers: &[&Keypair],
+) -> Transaction {
+    let blockhash = banks_client.get_latest_blockhash().await.unwrap();
+    let mut all_signers = vec![payer];
+    all_signers.extend_from_slice(signers);
+    Transaction::new_signed_with_payer(instructions, Some(&payer.pubkey()), &all_signers, blockhash)
+}
+
+pub async fn token_balance(banks_client: &mut BanksClient, key: Pubkey) -> u64 {
+    let account = banks_client.get_account(key).await.unwrap().unwrap();
+    spl_token::state::Account::unpack(&account.data).unwrap().amount
+}
+
+/// The transaction error a failed first instruction returns for `error`
+pub fn program_error(error: MultiHubSwapError) -> TransactionError {
+    TransactionError::InstructionError(0, InstructionError::Custom(error as u32))
+}
+
+/// Associated token account of `wallet` for `mint`
+pub fn associated_token_address(wallet: &Pubkey, mint: &Pubkey) -> Pubkey {
+    Pubkey::find_program_address(&[wallet.as_ref(), spl_token::id().as_ref(), mint.as_ref()], &ASSOCIATED_TOKEN_PROGRAM_ID).0
+}
+
+/// System account holding `lamports`
+pub fn add_wallet(program_test: &mut ProgramTest, key: Pubkey, lamports: u64) {
+    program_test.add_account(key, Account { lamports, ..Account::default() });
+}
+
+pub const SALE_SOL_RESERVE: u64 = 100_000_000_000;
+pub const SALE_YOT_RESERVE: u64 = 1_000_000_000_000;
+pub const SALE_AMOUNT_IN: u64 = 10_000_000_000;
+pub const SALE_WALLET_LAMPORTS: u64 = 10_000_000_000;
+/// Percent of each sale contributed to the central liquidity wallet
+pub const SALE_LP_RATE: u64 = 20;
+
+/// The program's SOL-YOT pool and one seller of SALE_AMOUNT_IN YOT through YotToSolSwapImmediate
+/// (discriminator 9). Set `program_state` and `central_wallet_lamports` before `run`
+pub struct YotSale {
+    pub program_id: Pubkey,
+    pub program_state: ProgramState,
+    pub user: Keypair,
+    pub user_yot: Pubkey,
+    pub user_yos: Pubkey,
+    /// SOL the central wallet holds before the sale when it takes SOL contributions
+    pub central_wallet_lamports: u64,
+}
+
+impl YotSale {
+    pub fn new() -> Self {
+        let program_id = Pubkey::new_unique();
+        let mut program_state = default_program_state(&program_id);
+        program_state.yot_mint = Pubkey::new_unique();
+        program_state.yos_mint = Pubkey::new_unique();
+        program_state.liquidity_wallet = Pubkey::new_unique();
+        program_state.lp_contribution_rate = SALE_LP_RATE;
+        Self {
+            program_id,
+            program_state,
+            user: Keypair::new(),
+            user_yot: Pubkey::new_unique(),
+            user_yos: Pubkey::new_unique(),
+            central_wallet_lamports: 0,
+        }
+    }
+
+    pub fn yot_pool(&self) -> Pubkey {
+        associated_token_address(&program_authority_address(&self.program_id), &self.program_state.yot_mint)
+    }
+
+    /// Run the sale and return the bank to inspect, with the lamports the user paid in rent
+    pub async fn run(&self) -> (BanksClient, u64) {
+        let authority = program_authority_address(&self.program_id);
+        let mut program_test = program_test(self.program_id);
+        add_program_state(&mut program_test, &self.program_id, &self.program_state);
+        // The program authority holds the SOL side of the pool while no SOL pool is pinned
+        add_wallet(&mut program_test, authority, SALE_SOL_RESERVE);
+        add_mint(&mut program_test, self.program_state.yot_mint, authority, SALE_YOT_RESERVE + SALE_AMOUNT_IN);
+        add_mint(&mut program_test, self.program_state.yos_mint, authority, 0);
+        add_token_account(&mut program_test, self.yot_pool(), self.program_state.yot_mint, authority, SALE_YOT_RESERVE);
+        // The central wallet takes YOT in a token account, SOL straight into its lamports
+        if self.program_state.sell_contribution_side == ProgramState::SELL_CONTRIBUTION_YOT_INPUT {
+            add_token_account(&mut program_test, self.program_state.liquidity_wallet, self.program_state.yot_mint, authority, 0);
+        } else if self.central_wallet_lamports > 0 {
+            add_wallet(&mut program_test, self.program_state.liquidity_wallet, self.central_wallet_lamports);
+        }
+        add_token_account(&mut program_test, self.user_yot, self.program_state.yot_mint, self.user.pubkey(), SALE_AMOUNT_IN);
+        add_token_account(&mut program_test, self.user_yos, self.program_state.yos_mint, self.user.pubkey(), 0);
+        add_wallet(&mut program_test, self.user.pubkey(), SALE_WALLET_LAMPORTS);
+        let (mut banks_client, payer, _) = program_test.start().await;
+
+        let transaction = transaction(&mut banks_client, &payer, &[self.swap_instruction()], &[&self.user]).await;
+        banks_client.process_transaction(transaction).await.unwrap();
+        // The user paid for their contribution, the global state and their history
+        let rent = banks_client.get_rent().await.unwrap();
+        let created_rent = [LiquidityContribution::LEN, GlobalContributionState::LEN, UserHistory::LEN]
+            .iter()
+            .fold(0u64, |total, len| total.saturating_add(rent.minimum_balance(*len)));
+        (banks_client, created_rent)
+    }
+
+    fn swap_instruction(&self) -> Instruction {
+        let user = self.user.pubkey();
+        let pda = |seeds: &[&[u8]]| Pubkey::find_program_address(seeds, &self.program_id).0;
+        let mut data = vec![9];
+        data.extend_from_slice(&SALE_AMOUNT_IN.to_le_bytes());
+        data.extend_from_slice(&1u64.to_le_bytes());
+        Instruction {
+            program_id: self.program_id,
+            accounts: vec![
+                AccountMeta::new(user, true),
+                AccountMeta::new_readonly(program_state_address(&self.program_id), false),
+                AccountMeta::new(program_authority_address(&self.program_id), false),
+                AccountMeta::new(program_authority_address(&self.program_id), false),
+                AccountMeta::new(self.yot_pool(), false),
+                AccountMeta::new(self.user_yot, false),
+                AccountMeta::new(self.program_state.liquidity_wallet, false),
+                AccountMeta::new(pda(&[b"liq", user.as_ref()]), false),
+                AccountMeta::new(self.program_state.yos_mint, false),
+                AccountMeta::new(self.user_yos, false),
+                AccountMeta::new_readonly(system_program::id(), false),
+                AccountMeta::new_readonly(spl_token::id(), false),
+                AccountMeta::new_readonly(sysvar::rent::id(), false),
+                AccountMeta::new(pda(&[b"global_contribution"]), false),
+                AccountMeta::new(pda(&[b"history", user.as_ref()]), false),
+                AccountMeta::new_readonly(self.program_state.yot_mint, false),
+            ],
+            data,
+        }
+    }
+}
@@ -0,0 +1,71 @@
+//! Compute budgets of the swap hot path
+//!
+//! Each test runs a swap and fails when the transaction used more compute units than its
+//! budget. Raise a budget only with a reason in the commit. The program's own units are only
+//! metered when it runs from its .so (`cargo test-sbf`); a native `cargo test` run meters just
+//! the token program CPIs, so it checks that the swaps go through but not the budget.
+//! Build with `--features metrics` to see what each phase of a swap cost in the logs.
+
+#![cfg(feature = "processor-multi-hub-swap")]
+// clippy.toml lets #[test] functions unwrap; the async tests and their helpers may too
+#![allow(clippy::unwrap_used)]
+
+mod common;
+
+use common::*;
+use solana_program_test::ProgramTest;
+use solana_sdk::{
+    instruction::Instruction,
+    pubkey::Pubkey,
+    signature::{Keypair, Signer},
+};
+
+/// Budget for a PoolSwap (discriminator 18) transaction
+const POOL_SWAP_BUDGET: u64 = 80_000;
+/// Budget for a RoutedPoolSwap (discriminator 25) transaction through MAX_ROUTE_HOPS pools
+const ROUTED_SWAP_BUDGET: u64 = 160_000;
+
+/// Run `swap` and return the compute units its transaction consumed
+async fn units_consumed(program_test: ProgramTest, user: &Keypair, swap: Instruction) -> u64 {
+    let (mut banks_client, payer, _) = program_test.start().await;
+    let transaction = transaction(&mut banks_client, &payer, &[swap], &[user]).await;
+    let outcome = banks_client.process_transaction_with_metadata(transaction).await.unwrap();
+    outcome.result.unwrap();
+    outcome.metadata.unwrap().compute_units_consumed
+}
+
+#[tokio::test]
+async fn pool_swap_stays_within_budget() {
+    let program_id = Pubkey::new_unique();
+    let mut program_test = program_test(program_id);
+    add_program_state(&mut program_test, &program_id, &default_program_state(&program_id));
+    let mints = [Pubkey::new_unique(), Pubkey::new_unique()];
+    let pools = add_pool_chain(&mut program_test, &program_id, &mints, 1_000_000_000);
+
+    let user = Keypair::new();
+    let (user_source, user_destination) = (Pubkey::new_unique(), Pubkey::new_unique());
+    add_token_account(&mut program_test, user_source, mints[0], user.pubkey(), 1_000_000);
+    add_token_account(&mut program_test, user_destination, mints[1], user.pubkey(), 0);
+
+    let swap = pool_swap_instruction(&program_id, &user.pubkey(), &pools[0], &user_source, &user_destination, 1_000_000, 1);
+    let units = units_consumed(program_test, &user, swap).await;
+    assert!(units <= POOL_SWAP_BUDGET, "pool swap used {units} compute units, budget {POOL_SWAP_BUDGET}");
+}
+
+#[tokio::test]
+async fn max_depth_routed_swap_stays_within_budget() {
+    let program_id = Pubkey::new_unique();
+    let mut program_test = program_test(program_id);
+    add_program_state(&mut program_test, &program_id, &default_program_state(&program_id));
+    let mints = [Pubkey::new_unique(), Pubkey::new_unique(), Pubkey::new_unique(), Pubkey::new_unique()];
+    let pools = add_pool_chain(&mut program_test, &program_id, &mints, 1_000_000_000);
+
+    let user = Keypair::new();
+    let (user_source, user_destination) = (Pubkey::new_unique(), Pubkey::new_unique());
+    add_token_account(&mut program_test, user_source, mints[0], user.pubkey(), 1_000_000);
+    add_token_account(&mut program_test, user_destination, mints[3], user.pubkey(), 0);
+
+    let swap = routed_pool_swap_instruction(&program_id, &user.pubkey(), &pools, &user_source, &user_destination, 1_000_000, 1);
+    let units = units_consumed(program_test, &user, swap).await;
+    assert!(units <= ROUTED_SWAP_BUDGET, "routed swap used {units} compute units, budget {ROUTED_SWAP_BUDGET}");
+}
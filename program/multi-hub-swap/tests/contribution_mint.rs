@@ -0,0 +1,146 @@
+//! Contribute (discriminator 2) and ContributeDelegated (discriminator 81) only credit YOT
+//! paid into the central liquidity wallet
+
+#![cfg(feature = "processor-multi-hub-swap")]
+// clippy.toml lets #[test] functions unwrap; the async tests and their helpers may too
+#![allow(clippy::unwrap_used)]
+
+mod common;
+
+use common::*;
+use multi_hub_swap::state::ProgramState;
+use solana_program::{program_option::COption, program_pack::Pack};
+use solana_program_test::ProgramTest;
+use solana_sdk::{
+    instruction::{AccountMeta, Instruction, InstructionError},
+    pubkey::Pubkey,
+    signature::{Keypair, Signer},
+    system_program,
+    transaction::TransactionError,
+};
+
+const AMOUNT: u64 = 1_000_000;
+
+/// A bank with a YOT mint, a worthless mint the user made, and the user's accounts of both
+struct Contributor {
+    program_id: Pubkey,
+    program_state: ProgramState,
+    user: Keypair,
+    worthless_mint: Pubkey,
+    user_worthless: Pubkey,
+    central_worthless: Pubkey,
+}
+
+impl Contributor {
+    fn new() -> Self {
+        let program_id = Pubkey::new_unique();
+        let mut program_state = default_program_state(&program_id);
+        program_state.yot_mint = Pubkey::new_unique();
+        program_state.yos_mint = Pubkey::new_unique();
+        program_state.liquidity_wallet = Pubkey::new_unique();
+        Self {
+            program_id,
+            program_state,
+            user: Keypair::new(),
+            worthless_mint: Pubkey::new_unique(),
+            user_worthless: Pubkey::new_unique(),
+            central_worthless: Pubkey::new_unique(),
+        }
+    }
+
+    /// The user's worthless tokens, approved to the program authority, and a central wallet
+    /// account of the same mint
+    fn program_test(&self) -> ProgramTest {
+        let mut program_test = program_test(self.program_id);
+        let authority = program_authority_address(&self.program_id);
+        add_program_state(&mut program_test, &self.program_id, &self.program_state);
+        add_mint(&mut program_test, self.worthless_mint, self.user.pubkey(), AMOUNT);
+        add_token_account(&mut program_test, self.central_worthless, self.worthless_mint, self.program_state.liquidity_wallet, 0);
+        add_wallet(&mut program_test, self.user.pubkey(), 1_000_000_000);
+
+        let mut data = vec![0u8; spl_token::state::Account::LEN];
+        spl_token::state::Account {
+            mint: self.worthless_mint,
+            owner: self.user.pubkey(),
+            amount: AMOUNT,
+            delegate: COption::Some(authority),
+            delegated_amount: AMOUNT,
+            state: spl_token::state::AccountState::Initialized,
+            ..Default::default()
+        }
+        .pack_into_slice(&mut data);
+        program_test.add_account(self.user_worthless, account_with_data(data, spl_token::id()));
+        program_test
+    }
+
+    fn contribution(&self) -> Pubkey {
+        Pubkey::find_program_address(&[b"liq", self.user.pubkey().as_ref()], &self.program_id).0
+    }
+
+    /// Accounts after the contribution PDA that settle rewards and register the amount
+    fn settlement_accounts(&self) -> Vec<AccountMeta> {
+        let user = self.user.pubkey();
+        vec![
+            AccountMeta::new(self.program_state.yos_mint, false),
+            AccountMeta::new(associated_token_address(&user, &self.program_state.yos_mint), false),
+            AccountMeta::new_readonly(program_state_address(&self.program_id), false),
+            AccountMeta::new(Pubkey::find_program_address(&[b"global_contribution"], &self.program_id).0, false),
+            AccountMeta::new(Pubkey::find_program_address(&[b"history", user.as_ref()], &self.program_id).0, false),
+        ]
+    }
+
+    async fn run(&self, instruction: Instruction) -> TransactionError {
+        let (mut banks_client, payer, _) = self.program_test().start().await;
+        let transaction = transaction(&mut banks_client, &payer, &[instruction], &[&self.user]).await;
+        let error = banks_client.process_transaction(transaction).await.unwrap_err().unwrap();
+        assert!(banks_client.get_account(self.contribution()).await.unwrap().is_none());
+        assert_eq!(token_balance(&mut banks_client, self.user_worthless).await, AMOUNT);
+        error
+    }
+}
+
+#[tokio::test]
+async fn contribute_rejects_a_token_other_than_yot() {
+    let contributor = Contributor::new();
+    let mut accounts = vec![
+        AccountMeta::new(contributor.user.pubkey(), true),
+        AccountMeta::new(contributor.user_worthless, false),
+        AccountMeta::new(contributor.central_worthless, false),
+        AccountMeta::new(contributor.contribution(), false),
+        AccountMeta::new_readonly(spl_token::id(), false),
+        AccountMeta::new_readonly(system_program::id(), false),
+    ];
+    accounts.extend(contributor.settlement_accounts());
+    let mut data = vec![2];
+    data.extend_from_slice(&AMOUNT.to_le_bytes());
+
+    let error = contributor.run(Instruction { program_id: contributor.program_id, accounts, data }).await;
+    assert_eq!(error, TransactionError::InstructionError(0, InstructionError::InvalidAccountData));
+}
+
+#[tokio::test]
+async fn delegated_contribution_rejects_a_token_other_than_yot() {
+    let contributor = Contributor::new();
+    let user = contributor.user.pubkey();
+    let settlement = contributor.settlement_accounts();
+    let accounts = vec![
+        AccountMeta::new(user, true),
+        AccountMeta::new(contributor.user_worthless, false),
+        AccountMeta::new(contributor.central_worthless, false),
+        AccountMeta::new(contributor.contribution(), false),
+        AccountMeta::new_readonly(program_authority_address(&contributor.program_id), false),
+        AccountMeta::new_readonly(spl_token::id(), false),
+        AccountMeta::new_readonly(system_program::id(), false),
+        settlement[0].clone(),
+        settlement[1].clone(),
+        settlement[2].clone(),
+        settlement[3].clone(),
+        settlement[4].clone(),
+    ];
+    let mut data = vec![81];
+    data.extend_from_slice(&AMOUNT.to_le_bytes());
+    data.extend_from_slice(user.as_ref());
+
+    let error = contributor.run(Instruction { program_id: contributor.program_id, accounts, data }).await;
+    assert_eq!(error, TransactionError::InstructionError(0, InstructionError::InvalidAccountData));
+}
@@ -0,0 +1,143 @@
+//! SetDustPolicy (discriminator 42) and CloseDustContribution (discriminator 43)
+
+#![cfg(feature = "processor-multi-hub-swap")]
+// clippy.toml lets #[test] functions unwrap; the async tests and their helpers may too
+#![allow(clippy::unwrap_used)]
+
+mod common;
+
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use common::*;
+use multi_hub_swap::constants::MAX_DUST_THRESHOLD;
+use multi_hub_swap::multi_hub_swap_complete::LiquidityContribution;
+use multi_hub_swap::state::ProgramState;
+use solana_program_test::ProgramTest;
+use solana_sdk::{
+    instruction::{AccountMeta, Instruction, InstructionError},
+    pubkey::Pubkey,
+    signature::{Keypair, Signer},
+    system_program,
+    transaction::TransactionError,
+};
+
+const DUST: u64 = 5_000;
+const VAULT_BALANCE: u64 = 10_000_000;
+const WALLET_LAMPORTS: u64 = 10_000_000_000;
+
+/// A dust contribution of DUST YOT, claimed just now so no reward is owed
+struct Dust {
+    program_id: Pubkey,
+    program_state: ProgramState,
+    admin: Keypair,
+    user: Pubkey,
+    contribution: Pubkey,
+    vault: Pubkey,
+    user_yot: Pubkey,
+}
+
+impl Dust {
+    fn new() -> Self {
+        let program_id = Pubkey::new_unique();
+        let admin = Keypair::new();
+        let mut program_state = default_program_state(&program_id);
+        program_state.admin = admin.pubkey();
+        program_state.yot_mint = Pubkey::new_unique();
+        program_state.dust_threshold = MAX_DUST_THRESHOLD;
+        let user = Pubkey::new_unique();
+        Self {
+            contribution: Pubkey::find_program_address(&[b"liq", user.as_ref()], &program_id).0,
+            vault: associated_token_address(&program_authority_address(&program_id), &program_state.yot_mint),
+            program_id,
+            program_state,
+            admin,
+            user,
+            user_yot: Pubkey::new_unique(),
+        }
+    }
+
+    fn program_test(&self) -> ProgramTest {
+        let mut program_test = program_test(self.program_id);
+        let authority = program_authority_address(&self.program_id);
+        add_program_state(&mut program_test, &self.program_id, &self.program_state);
+        add_mint(&mut program_test, self.program_state.yot_mint, authority, VAULT_BALANCE);
+        add_token_account(&mut program_test, self.vault, self.program_state.yot_mint, authority, VAULT_BALANCE);
+        add_token_account(&mut program_test, self.user_yot, self.program_state.yot_mint, self.user, 0);
+        add_wallet(&mut program_test, self.user, WALLET_LAMPORTS);
+        add_wallet(&mut program_test, self.admin.pubkey(), WALLET_LAMPORTS);
+
+        let mut contribution = LiquidityContribution::unpack(&[0u8; LiquidityContribution::LEN]).unwrap();
+        contribution.user = self.user;
+        contribution.contributed_amount = DUST;
+        contribution.last_claim_time = SystemTime::now().duration_since(UNIX_EPOCH).unwrap().as_secs() as i64;
+        contribution.bump = Pubkey::find_program_address(&[b"liq", self.user.as_ref()], &self.program_id).1;
+        let mut data = vec![0u8; LiquidityContribution::LEN];
+        contribution.pack(&mut data).unwrap();
+        program_test.add_account(self.contribution, account_with_data(data, self.program_id));
+        program_test
+    }
+
+    fn close_instruction(&self, cranker: &Pubkey) -> Instruction {
+        let pda = |seeds: &[&[u8]]| Pubkey::find_program_address(seeds, &self.program_id).0;
+        Instruction {
+            program_id: self.program_id,
+            accounts: vec![
+                AccountMeta::new(*cranker, true),
+                AccountMeta::new_readonly(program_state_address(&self.program_id), false),
+                AccountMeta::new(self.contribution, false),
+                AccountMeta::new(self.user, false),
+                AccountMeta::new(pda(&[b"global_contribution"]), false),
+                AccountMeta::new(pda(&[b"sol_fee_vault"]), false),
+                AccountMeta::new_readonly(program_authority_address(&self.program_id), false),
+                AccountMeta::new(self.vault, false),
+                AccountMeta::new(self.user_yot, false),
+                AccountMeta::new_readonly(spl_token::id(), false),
+            ],
+            data: vec![43],
+        }
+    }
+
+    fn policy_instruction(&self, dust_threshold: u64) -> Instruction {
+        let mut data = vec![42];
+        data.extend_from_slice(&dust_threshold.to_le_bytes());
+        data.extend_from_slice(&0u64.to_le_bytes());
+        Instruction {
+            program_id: self.program_id,
+            accounts: vec![
+                AccountMeta::new(self.admin.pubkey(), true),
+                AccountMeta::new(program_state_address(&self.program_id), false),
+                AccountMeta::new_readonly(system_program::id(), false),
+            ],
+            data,
+        }
+    }
+}
+
+#[tokio::test]
+async fn closing_dust_refunds_the_principal() {
+    let dust = Dust::new();
+    let (mut banks_client, payer, _) = dust.program_test().start().await;
+    let contribution_rent = banks_client.get_balance(dust.contribution).await.unwrap();
+
+    let transaction = transaction(&mut banks_client, &payer, &[dust.close_instruction(&payer.pubkey())], &[]).await;
+    banks_client.process_transaction(transaction).await.unwrap();
+
+    // The principal leaves the vault for the user, the rent goes back with it
+    assert_eq!(token_balance(&mut banks_client, dust.user_yot).await, DUST);
+    assert_eq!(token_balance(&mut banks_client, dust.vault).await, VAULT_BALANCE - DUST);
+    assert!(banks_client.get_account(dust.contribution).await.unwrap().is_none());
+    assert_eq!(banks_client.get_balance(dust.user).await.unwrap(), WALLET_LAMPORTS + contribution_rent);
+}
+
+#[tokio::test]
+async fn dust_threshold_is_capped() {
+    let dust = Dust::new();
+    let (mut banks_client, payer, _) = dust.program_test().start().await;
+
+    let above_cap = transaction(&mut banks_client, &payer, &[dust.policy_instruction(MAX_DUST_THRESHOLD + 1)], &[&dust.admin]).await;
+    let result = banks_client.process_transaction(above_cap).await.map_err(|error| error.unwrap());
+    assert_eq!(result, Err(TransactionError::InstructionError(0, InstructionError::InvalidArgument)));
+
+    let at_cap = transaction(&mut banks_client, &payer, &[dust.policy_instruction(MAX_DUST_THRESHOLD)], &[&dust.admin]).await;
+    banks_client.process_transaction(at_cap).await.unwrap();
+}
@@ -0,0 +1,257 @@
+//! ExitPosition (discriminator 137): settle, withdraw everything and close the contribution
+
+#![cfg(feature = "processor-multi-hub-swap")]
+// clippy.toml lets #[test] functions unwrap; the async tests and their helpers may too
+#![allow(clippy::unwrap_used)]
+
+mod common;
+
+use common::*;
+use multi_hub_swap::multi_hub_swap_complete::{GlobalContributionState, LiquidityContribution, MultiHubSwapError, UserHistory};
+use multi_hub_swap::state::ProgramState;
+use solana_program_test::{BanksClient, ProgramTest};
+use solana_sdk::{
+    instruction::{AccountMeta, Instruction, InstructionError},
+    pubkey::Pubkey,
+    rent::Rent,
+    signature::{Keypair, Signer},
+    system_instruction, system_program,
+    transaction::TransactionError,
+};
+
+const CONTRIBUTED: u64 = 1_000_000;
+const VAULT_BALANCE: u64 = 10_000_000;
+const WALLET_LAMPORTS: u64 = 10_000_000_000;
+
+/// One user's contribution and the accounts ExitPosition takes for it
+struct Position {
+    program_id: Pubkey,
+    program_state: ProgramState,
+    user: Keypair,
+    contribution: Pubkey,
+    contribution_bump: u8,
+    vault: Pubkey,
+    user_yot: Pubkey,
+    user_yos: Pubkey,
+}
+
+impl Position {
+    fn new() -> Self {
+        let program_id = Pubkey::new_unique();
+        let user = Keypair::new();
+        let mut program_state = default_program_state(&program_id);
+        program_state.yot_mint = Pubkey::new_unique();
+        program_state.yos_mint = Pubkey::new_unique();
+        let (contribution, contribution_bump) = Pubkey::find_program_address(&[b"liq", user.pubkey().as_ref()], &program_id);
+        Self {
+            vault: associated_token_address(&program_authority_address(&program_id), &program_state.yot_mint),
+            program_id,
+            program_state,
+            user,
+            contribution,
+            contribution_bump,
+            user_yot: Pubkey::new_unique(),
+            user_yos: Pubkey::new_unique(),
+        }
+    }
+
+    /// A contribution of CONTRIBUTED YOT whose last claim is long past
+    fn contribution(&self) -> LiquidityContribution {
+        let mut contribution = LiquidityContribution::unpack(&[0u8; LiquidityContribution::LEN]).unwrap();
+        contribution.user = self.user.pubkey();
+        contribution.contributed_amount = CONTRIBUTED;
+        contribution.bump = self.contribution_bump;
+        contribution
+    }
+
+    /// Bank holding the program state, the mints, the vault, the user's accounts and `contribution`
+    fn program_test(&self, contribution: &LiquidityContribution) -> ProgramTest {
+        let mut program_test = program_test(self.program_id);
+        let authority = program_authority_address(&self.program_id);
+        add_program_state(&mut program_test, &self.program_id, &self.program_state);
+        add_mint(&mut program_test, self.program_state.yot_mint, authority, VAULT_BALANCE);
+        add_mint(&mut program_test, self.program_state.yos_mint, authority, 0);
+        add_token_account(&mut program_test, self.vault, self.program_state.yot_mint, authority, VAULT_BALANCE);
+        add_token_account(&mut program_test, self.user_yot, self.program_state.yot_mint, self.user.pubkey(), 0);
+        add_token_account(&mut program_test, self.user_yos, self.program_state.yos_mint, self.user.pubkey(), 0);
+        add_wallet(&mut program_test, self.user.pubkey(), WALLET_LAMPORTS);
+
+        let mut data = vec![0u8; LiquidityContribution::LEN];
+        contribution.pack(&mut data).unwrap();
+        program_test.add_account(self.contribution, account_with_data(data, self.program_id));
+        program_test
+    }
+
+    /// ExitPosition with the global contribution state, then the program authority that
+    /// signs the payouts and any `extra` accounts
+    fn exit_instruction(&self, extra: &[AccountMeta]) -> Instruction {
+        let global_state = Pubkey::find_program_address(&[b"global_contribution"], &self.program_id).0;
+        let user_history = Pubkey::find_program_address(&[b"history", self.user.pubkey().as_ref()], &self.program_id).0;
+        let mut accounts = vec![
+            AccountMeta::new(self.user.pubkey(), true),
+            AccountMeta::new(self.contribution, false),
+            AccountMeta::new(self.vault, false),
+            AccountMeta::new(self.user_yot, false),
+            AccountMeta::new_readonly(spl_token::id(), false),
+            AccountMeta::new_readonly(program_state_address(&self.program_id), false),
+            AccountMeta::new(global_state, false),
+            AccountMeta::new(self.program_state.yos_mint, false),
+            AccountMeta::new(self.user_yos, false),
+            AccountMeta::new_readonly(system_program::id(), false),
+            AccountMeta::new(user_history, false),
+            AccountMeta::new(program_authority_address(&self.program_id), false),
+        ];
+        accounts.extend_from_slice(extra);
+        Instruction { program_id: self.program_id, accounts, data: vec![137] }
+    }
+}
+
+/// Run `instructions` signed by the payer and the position's user
+async fn run(banks_client: &mut BanksClient, payer: &Keypair, position: &Position, instructions: &[Instruction]) -> Result<(), TransactionError> {
+    let transaction = transaction(banks_client, payer, instructions, &[&position.user]).await;
+    banks_client.process_transaction(transaction).await.map_err(|error| error.unwrap())
+}
+
+/// Lamports the program took from the user to create the global state and user history
+fn created_rent(rent: &Rent) -> u64 {
+    rent.minimum_balance(GlobalContributionState::LEN).saturating_add(rent.minimum_balance(UserHistory::LEN))
+}
+
+#[tokio::test]
+async fn exit_settles_flat_rewards_and_pays_back_the_principal() {
+    let position = Position::new();
+    let (mut banks_client, payer, _) = position.program_test(&position.contribution()).start().await;
+    let rent = banks_client.get_rent().await.unwrap();
+    let contribution_rent = banks_client.get_balance(position.contribution).await.unwrap();
+
+    run(&mut banks_client, &payer, &position, &[position.exit_instruction(&[])]).await.unwrap();
+
+    // A full claim period has passed, so one period's flat reward is minted
+    let reward = CONTRIBUTED * LiquidityContribution::WEEKLY_REWARD_RATE_BPS / 10_000;
+    assert_eq!(token_balance(&mut banks_client, position.user_yos).await, reward);
+    assert_eq!(token_balance(&mut banks_client, position.user_yot).await, CONTRIBUTED);
+    assert_eq!(token_balance(&mut banks_client, position.vault).await, VAULT_BALANCE - CONTRIBUTED);
+    // The contribution is closed and its rent is back with the user
+    assert!(banks_client.get_account(position.contribution).await.unwrap().is_none());
+    assert_eq!(
+        banks_client.get_balance(position.user.pubkey()).await.unwrap(),
+        WALLET_LAMPORTS + contribution_rent - created_rent(&rent),
+    );
+}
+
+#[tokio::test]
+async fn exit_redeems_lp_held_principal_at_pool_value() {
+    let mut position = Position::new();
+    let authority = program_authority_address(&position.program_id);
+    let (yot_pool, lp_mint) = (Pubkey::new_unique(), Pubkey::new_unique());
+    let lp_account = associated_token_address(&authority, &lp_mint);
+    position.program_state.sol_pool = authority;
+    position.program_state.yot_pool = yot_pool;
+    position.program_state.sol_yot_lp_mint = lp_mint;
+
+    // 400,000 of the principal was paid in as 100 of the 1,000 LP tokens
+    let mut contribution = position.contribution();
+    contribution.lp_principal = 400_000;
+    contribution.lp_amount = 100;
+    let mut program_test = position.program_test(&contribution);
+    add_wallet(&mut program_test, authority, WALLET_LAMPORTS);
+    add_token_account(&mut program_test, yot_pool, position.program_state.yot_mint, authority, 5_000_000);
+    add_mint(&mut program_test, lp_mint, authority, 1_000);
+    add_token_account(&mut program_test, lp_account, lp_mint, authority, 1_000);
+    let (mut banks_client, payer, _) = program_test.start().await;
+    let rent = banks_client.get_rent().await.unwrap();
+    let contribution_rent = banks_client.get_balance(position.contribution).await.unwrap();
+
+    let lp_accounts = [
+        AccountMeta::new(yot_pool, false),
+        AccountMeta::new(lp_mint, false),
+        AccountMeta::new(lp_account, false),
+    ];
+    run(&mut banks_client, &payer, &position, &[position.exit_instruction(&lp_accounts)]).await.unwrap();
+
+    // The vault returns the rest of the principal, the LP a tenth of each pool side
+    assert_eq!(token_balance(&mut banks_client, position.vault).await, VAULT_BALANCE - 600_000);
+    assert_eq!(token_balance(&mut banks_client, position.user_yot).await, 600_000 + 500_000);
+    assert_eq!(token_balance(&mut banks_client, lp_account).await, 900);
+    assert_eq!(
+        banks_client.get_balance(position.user.pubkey()).await.unwrap(),
+        WALLET_LAMPORTS + WALLET_LAMPORTS / 10 + contribution_rent - created_rent(&rent),
+    );
+}
+
+#[tokio::test]
+async fn exit_refunds_rent_to_the_recorded_rent_payer() {
+    let position = Position::new();
+    let sponsor = Pubkey::new_unique();
+    let mut contribution = position.contribution();
+    contribution.rent_payer = sponsor;
+    let mut program_test = position.program_test(&contribution);
+    add_wallet(&mut program_test, sponsor, WALLET_LAMPORTS);
+    let (mut banks_client, payer, _) = program_test.start().await;
+    let rent = banks_client.get_rent().await.unwrap();
+    let contribution_rent = banks_client.get_balance(position.contribution).await.unwrap();
+
+    // The rent payer must be passed to receive it
+    let result = run(&mut banks_client, &payer, &position, &[position.exit_instruction(&[])]).await;
+    assert_eq!(result, Err(TransactionError::InstructionError(0, InstructionError::NotEnoughAccountKeys)));
+
+    run(&mut banks_client, &payer, &position, &[position.exit_instruction(&[AccountMeta::new(sponsor, false)])]).await.unwrap();
+    assert_eq!(banks_client.get_balance(sponsor).await.unwrap(), WALLET_LAMPORTS + contribution_rent);
+    assert_eq!(banks_client.get_balance(position.user.pubkey()).await.unwrap(), WALLET_LAMPORTS - created_rent(&rent));
+}
+
+#[tokio::test]
+async fn exit_zeroes_the_contribution_data() {
+    let position = Position::new();
+    let (mut banks_client, payer, _) = position.program_test(&position.contribution()).start().await;
+
+    // Funding the closed account later in the same transaction keeps it alive to inspect
+    let revive = system_instruction::transfer(&payer.pubkey(), &position.contribution, WALLET_LAMPORTS / 10);
+    run(&mut banks_client, &payer, &position, &[position.exit_instruction(&[]), revive]).await.unwrap();
+
+    let account = banks_client.get_account(position.contribution).await.unwrap().unwrap();
+    assert_eq!(account.data.len(), LiquidityContribution::LEN);
+    assert!(account.data.iter().all(|byte| *byte == 0));
+}
+
+#[tokio::test]
+async fn exit_closes_an_already_emptied_position() {
+    let position = Position::new();
+    let mut contribution = position.contribution();
+    contribution.contributed_amount = 0;
+    let (mut banks_client, payer, _) = position.program_test(&contribution).start().await;
+
+    run(&mut banks_client, &payer, &position, &[position.exit_instruction(&[])]).await.unwrap();
+
+    assert!(banks_client.get_account(position.contribution).await.unwrap().is_none());
+    assert_eq!(token_balance(&mut banks_client, position.user_yot).await, 0);
+    assert_eq!(token_balance(&mut banks_client, position.user_yos).await, 0);
+    assert_eq!(token_balance(&mut banks_client, position.vault).await, VAULT_BALANCE);
+}
+
+#[tokio::test]
+async fn exit_is_disabled_with_claim_rewards_or_withdraw_liquidity() {
+    // ClaimRewards (3) and WithdrawLiquidity (5)
+    for tag in [3, 5] {
+        let mut position = Position::new();
+        position.program_state.disabled_instructions = 1 << tag;
+        let (mut banks_client, payer, _) = position.program_test(&position.contribution()).start().await;
+
+        let result = run(&mut banks_client, &payer, &position, &[position.exit_instruction(&[])]).await;
+        assert_eq!(result, Err(program_error(MultiHubSwapError::InstructionDisabled)));
+        let account = banks_client.get_account(position.contribution).await.unwrap().unwrap();
+        assert_eq!(LiquidityContribution::unpack(&account.data).unwrap().contributed_amount, CONTRIBUTED);
+    }
+}
+
+#[tokio::test]
+async fn exit_still_works_after_shutdown() {
+    let mut position = Position::new();
+    position.program_state.shut_down = 1;
+    let (mut banks_client, payer, _) = position.program_test(&position.contribution()).start().await;
+
+    run(&mut banks_client, &payer, &position, &[position.exit_instruction(&[])]).await.unwrap();
+
+    assert!(banks_client.get_account(position.contribution).await.unwrap().is_none());
+    assert_eq!(token_balance(&mut banks_client, position.user_yot).await, CONTRIBUTED);
+}
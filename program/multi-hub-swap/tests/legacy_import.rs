@@ -0,0 +1,97 @@
+//! ImportContribution (discriminator 41) and ClaimRewardsCombined (discriminator 83): only
+//! contributions owned by the earlier deployment, LEGACY_PROGRAM_ID, can be folded in here
+
+#![cfg(feature = "processor-multi-hub-swap")]
+// clippy.toml lets #[test] functions unwrap; the async tests and their helpers may too
+#![allow(clippy::unwrap_used)]
+
+mod common;
+
+use common::*;
+use multi_hub_swap::multi_hub_swap_complete::LiquidityContribution;
+use solana_program_test::ProgramTest;
+use solana_sdk::{
+    instruction::{AccountMeta, Instruction, InstructionError},
+    pubkey::Pubkey,
+    signature::{Keypair, Signer},
+    system_program,
+    transaction::TransactionError,
+};
+
+/// A legacy contribution of `amount` for `user` at its [b"liq", user] address under `owner`
+fn add_legacy_contribution(program_test: &mut ProgramTest, owner: &Pubkey, user: &Pubkey, amount: u64) -> Pubkey {
+    let (address, bump) = Pubkey::find_program_address(&[b"liq", user.as_ref()], owner);
+    let mut contribution = LiquidityContribution::unpack(&[0u8; LiquidityContribution::LEN]).unwrap();
+    contribution.user = *user;
+    contribution.contributed_amount = amount;
+    contribution.bump = bump;
+    let mut data = vec![0u8; LiquidityContribution::LEN];
+    contribution.pack(&mut data).unwrap();
+    program_test.add_account(address, account_with_data(data, *owner));
+    address
+}
+
+/// The accounts ImportContribution and ClaimRewardsCombined share, signed by `signer`
+fn import_accounts(program_id: &Pubkey, signer: &Pubkey, user: &Pubkey, legacy_contribution: &Pubkey) -> Vec<AccountMeta> {
+    vec![
+        AccountMeta::new(*signer, true),
+        AccountMeta::new_readonly(program_state_address(program_id), false),
+        AccountMeta::new_readonly(*legacy_contribution, false),
+        AccountMeta::new(Pubkey::find_program_address(&[b"liq", user.as_ref()], program_id).0, false),
+        AccountMeta::new(Pubkey::find_program_address(&[b"import", legacy_contribution.as_ref()], program_id).0, false),
+        AccountMeta::new(Pubkey::find_program_address(&[b"global_contribution"], program_id).0, false),
+        AccountMeta::new(Pubkey::find_program_address(&[b"history", user.as_ref()], program_id).0, false),
+        AccountMeta::new(Pubkey::new_unique(), false),
+        AccountMeta::new(Pubkey::new_unique(), false),
+        AccountMeta::new_readonly(system_program::id(), false),
+        AccountMeta::new_readonly(spl_token::id(), false),
+    ]
+}
+
+#[tokio::test]
+async fn import_rejects_a_contribution_owned_by_another_program() {
+    let (program_id, admin, user) = (Pubkey::new_unique(), Keypair::new(), Pubkey::new_unique());
+    let mut program_test = program_test(program_id);
+    let mut program_state = default_program_state(&program_id);
+    program_state.admin = admin.pubkey();
+    add_program_state(&mut program_test, &program_id, &program_state);
+    add_wallet(&mut program_test, admin.pubkey(), 1_000_000_000);
+
+    // A program anyone could deploy, holding a contribution at the canonical address under it
+    let forger = Pubkey::new_unique();
+    let forged = add_legacy_contribution(&mut program_test, &forger, &user, u64::MAX / 2);
+
+    let (mut banks_client, payer, _) = program_test.start().await;
+    let import = Instruction {
+        program_id,
+        accounts: import_accounts(&program_id, &admin.pubkey(), &user, &forged),
+        data: vec![41],
+    };
+    let transaction = transaction(&mut banks_client, &payer, &[import], &[&admin]).await;
+    let error = banks_client.process_transaction(transaction).await.unwrap_err().unwrap();
+    assert_eq!(error, TransactionError::InstructionError(0, InstructionError::IllegalOwner));
+}
+
+#[tokio::test]
+async fn combined_claim_rejects_a_contribution_owned_by_another_program() {
+    let (program_id, user) = (Pubkey::new_unique(), Keypair::new());
+    let mut program_test = program_test(program_id);
+    add_program_state(&mut program_test, &program_id, &default_program_state(&program_id));
+    add_wallet(&mut program_test, user.pubkey(), 1_000_000_000);
+
+    // The user's own forged legacy contribution, at the canonical address under their program
+    let forger = Pubkey::new_unique();
+    let forged = add_legacy_contribution(&mut program_test, &forger, &user.pubkey(), u64::MAX / 2);
+
+    let (mut banks_client, payer, _) = program_test.start().await;
+    let claim = Instruction {
+        program_id,
+        accounts: import_accounts(&program_id, &user.pubkey(), &user.pubkey(), &forged),
+        data: vec![83],
+    };
+    let transaction = transaction(&mut banks_client, &payer, &[claim], &[&user]).await;
+    let error = banks_client.process_transaction(transaction).await.unwrap_err().unwrap();
+    assert_eq!(error, TransactionError::InstructionError(0, InstructionError::IllegalOwner));
+    let contribution = Pubkey::find_program_address(&[b"liq", user.pubkey().as_ref()], &program_id).0;
+    assert!(banks_client.get_account(contribution).await.unwrap().is_none());
+}
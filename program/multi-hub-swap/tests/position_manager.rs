@@ -0,0 +1,196 @@
+//! Position managers set by SetPositionManager: they may compound a user's rewards
+//! (CompoundRewards, discriminator 127) but never withdraw or exit the position
+
+#![cfg(feature = "processor-multi-hub-swap")]
+// clippy.toml lets #[test] functions unwrap; the async tests and their helpers may too
+#![allow(clippy::unwrap_used)]
+
+mod common;
+
+use common::*;
+use multi_hub_swap::multi_hub_swap_complete::LiquidityContribution;
+use multi_hub_swap::state::ProgramState;
+use multi_hub_swap_core::calculate_pool_swap_output_bps;
+use solana_program_test::BanksClient;
+use solana_sdk::{
+    instruction::{AccountMeta, Instruction, InstructionError},
+    pubkey::Pubkey,
+    signature::{Keypair, Signer},
+    system_program,
+    transaction::TransactionError,
+};
+
+const CONTRIBUTED: u64 = 1_000_000;
+const RESERVE: u64 = 1_000_000_000;
+const WALLET_LAMPORTS: u64 = 10_000_000_000;
+
+/// A user's contribution with a manager, and a YOT-YOS pool to compound through
+struct ManagedPosition {
+    program_id: Pubkey,
+    program_state: ProgramState,
+    user: Keypair,
+    manager: Keypair,
+    contribution: Pubkey,
+    pools: Vec<TestPool>,
+    liquidity_token: Pubkey,
+    vault: Pubkey,
+    user_yot: Pubkey,
+    user_yos: Pubkey,
+}
+
+impl ManagedPosition {
+    /// Start a bank holding the position, its accounts and the pool
+    async fn start() -> (Self, BanksClient, Keypair) {
+        let program_id = Pubkey::new_unique();
+        let authority = program_authority_address(&program_id);
+        let (user, manager) = (Keypair::new(), Keypair::new());
+        let mut program_state = default_program_state(&program_id);
+        program_state.yot_mint = Pubkey::new_unique();
+        program_state.yos_mint = Pubkey::new_unique();
+        program_state.liquidity_wallet = Pubkey::new_unique();
+
+        let mut program_test = program_test(program_id);
+        add_program_state(&mut program_test, &program_id, &program_state);
+        let pools = add_pool_chain(&mut program_test, &program_id, &[program_state.yos_mint, program_state.yot_mint], RESERVE);
+        let position = Self {
+            contribution: Pubkey::find_program_address(&[b"liq", user.pubkey().as_ref()], &program_id).0,
+            vault: associated_token_address(&authority, &program_state.yot_mint),
+            liquidity_token: Pubkey::new_unique(),
+            user_yot: Pubkey::new_unique(),
+            user_yos: Pubkey::new_unique(),
+            program_id,
+            program_state,
+            user,
+            manager,
+            pools,
+        };
+        let yot_mint = position.program_state.yot_mint;
+        add_token_account(&mut program_test, position.liquidity_token, yot_mint, position.program_state.liquidity_wallet, 0);
+        add_token_account(&mut program_test, position.vault, yot_mint, authority, CONTRIBUTED);
+        add_token_account(&mut program_test, position.user_yot, yot_mint, position.user.pubkey(), 0);
+        add_token_account(&mut program_test, position.user_yos, position.program_state.yos_mint, position.user.pubkey(), 0);
+        add_wallet(&mut program_test, position.user.pubkey(), WALLET_LAMPORTS);
+        add_wallet(&mut program_test, position.manager.pubkey(), WALLET_LAMPORTS);
+
+        // A contribution whose last claim is long past, managed by `manager`
+        let bump = Pubkey::find_program_address(&[b"liq", position.user.pubkey().as_ref()], &program_id).1;
+        let mut contribution = LiquidityContribution::unpack(&[0u8; LiquidityContribution::LEN]).unwrap();
+        contribution.user = position.user.pubkey();
+        contribution.contributed_amount = CONTRIBUTED;
+        contribution.bump = bump;
+        contribution.manager = position.manager.pubkey();
+        let mut data = vec![0u8; LiquidityContribution::LEN];
+        contribution.pack(&mut data).unwrap();
+        program_test.add_account(position.contribution, account_with_data(data, program_id));
+
+        let (banks_client, payer, _) = program_test.start().await;
+        (position, banks_client, payer)
+    }
+
+    fn global_state(&self) -> Pubkey {
+        Pubkey::find_program_address(&[b"global_contribution"], &self.program_id).0
+    }
+
+    fn user_history(&self) -> Pubkey {
+        Pubkey::find_program_address(&[b"history", self.user.pubkey().as_ref()], &self.program_id).0
+    }
+
+    /// CompoundRewards signed by `signer` for the user
+    fn compound_instruction(&self, signer: &Pubkey) -> Instruction {
+        let pool = &self.pools[0];
+        let mut data = vec![127];
+        data.extend_from_slice(&1u64.to_le_bytes());
+        Instruction {
+            program_id: self.program_id,
+            accounts: vec![
+                AccountMeta::new(*signer, true),
+                AccountMeta::new_readonly(self.user.pubkey(), false),
+                AccountMeta::new(self.contribution, false),
+                AccountMeta::new_readonly(program_state_address(&self.program_id), false),
+                AccountMeta::new_readonly(program_authority_address(&self.program_id), false),
+                AccountMeta::new_readonly(pool_registry_address(&self.program_id), false),
+                AccountMeta::new(pool.pool, false),
+                AccountMeta::new(pool.reserve_a, false),
+                AccountMeta::new(pool.reserve_b, false),
+                AccountMeta::new(self.liquidity_token, false),
+                AccountMeta::new(self.program_state.yos_mint, false),
+                AccountMeta::new(self.global_state(), false),
+                AccountMeta::new(self.user_history(), false),
+                AccountMeta::new_readonly(system_program::id(), false),
+                AccountMeta::new_readonly(spl_token::id(), false),
+                AccountMeta::new_readonly(self.program_state.yot_mint, false),
+            ],
+            data,
+        }
+    }
+
+    /// WithdrawLiquidity (5) or ExitPosition (137) with the manager signing in the user's place
+    fn withdraw_instruction_signed_by_the_manager(&self, tag: u8) -> Instruction {
+        Instruction {
+            program_id: self.program_id,
+            accounts: vec![
+                AccountMeta::new(self.user.pubkey(), false),
+                AccountMeta::new(self.contribution, false),
+                AccountMeta::new(self.vault, false),
+                AccountMeta::new(self.user_yot, false),
+                AccountMeta::new_readonly(spl_token::id(), false),
+                AccountMeta::new_readonly(program_state_address(&self.program_id), false),
+                AccountMeta::new(self.global_state(), false),
+                AccountMeta::new(self.program_state.yos_mint, false),
+                AccountMeta::new(self.user_yos, false),
+                AccountMeta::new_readonly(system_program::id(), false),
+                AccountMeta::new(self.user_history(), false),
+                AccountMeta::new(program_authority_address(&self.program_id), false),
+                AccountMeta::new_readonly(self.manager.pubkey(), true),
+            ],
+            data: vec![tag],
+        }
+    }
+
+    async fn contributed_amount(&self, banks_client: &mut BanksClient) -> u64 {
+        let account = banks_client.get_account(self.contribution).await.unwrap().unwrap();
+        LiquidityContribution::unpack(&account.data).unwrap().contributed_amount
+    }
+}
+
+#[tokio::test]
+async fn manager_compounds_the_users_rewards() {
+    let (position, mut banks_client, payer) = ManagedPosition::start().await;
+
+    let compound = position.compound_instruction(&position.manager.pubkey());
+    let transaction = transaction(&mut banks_client, &payer, &[compound], &[&position.manager]).await;
+    banks_client.process_transaction(transaction).await.unwrap();
+
+    // One period's flat reward is swapped to YOT and added to the user's contribution
+    let reward = CONTRIBUTED * LiquidityContribution::WEEKLY_REWARD_RATE_BPS / 10_000;
+    let yot_out = calculate_pool_swap_output_bps(reward, RESERVE, RESERVE, 0).unwrap();
+    assert_eq!(position.contributed_amount(&mut banks_client).await, CONTRIBUTED + yot_out);
+    assert_eq!(token_balance(&mut banks_client, position.liquidity_token).await, yot_out);
+    assert_eq!(token_balance(&mut banks_client, position.user_yos).await, 0);
+}
+
+#[tokio::test]
+async fn stranger_cannot_compound_the_position() {
+    let (position, mut banks_client, payer) = ManagedPosition::start().await;
+    let stranger = Keypair::new();
+
+    let compound = position.compound_instruction(&stranger.pubkey());
+    let transaction = transaction(&mut banks_client, &payer, &[compound], &[&stranger]).await;
+    let error = banks_client.process_transaction(transaction).await.unwrap_err().unwrap();
+    assert_eq!(error, TransactionError::InstructionError(0, InstructionError::MissingRequiredSignature));
+}
+
+#[tokio::test]
+async fn manager_cannot_withdraw_or_exit_the_position() {
+    let (position, mut banks_client, payer) = ManagedPosition::start().await;
+
+    // WithdrawLiquidity (5) and ExitPosition (137)
+    for tag in [5, 137] {
+        let withdraw = position.withdraw_instruction_signed_by_the_manager(tag);
+        let transaction = transaction(&mut banks_client, &payer, &[withdraw], &[&position.manager]).await;
+        let error = banks_client.process_transaction(transaction).await.unwrap_err().unwrap();
+        assert_eq!(error, TransactionError::InstructionError(0, InstructionError::MissingRequiredSignature));
+    }
+    assert_eq!(position.contributed_amount(&mut banks_client).await, CONTRIBUTED);
+    assert_eq!(token_balance(&mut banks_client, position.vault).await, CONTRIBUTED);
+}
@@ -0,0 +1,86 @@
+//! Routed pool swaps at the maximum route depth, end to end
+
+#![cfg(feature = "processor-multi-hub-swap")]
+// clippy.toml lets #[test] functions unwrap; the async tests and their helpers may too
+#![allow(clippy::unwrap_used)]
+
+mod common;
+
+use common::*;
+use multi_hub_swap::multi_hub_swap_complete::MultiHubSwapError;
+use multi_hub_swap_core::calculate_pool_swap_output_bps;
+use solana_sdk::{
+    pubkey::Pubkey,
+    signature::{Keypair, Signer},
+};
+
+const MAX_ROUTE_HOPS: usize = 3;
+const RESERVE: u64 = 1_000_000_000;
+const AMOUNT_IN: u64 = 5_000_000;
+
+#[tokio::test]
+async fn max_depth_route_pays_through_every_pool() {
+    let program_id = Pubkey::new_unique();
+    let mut program_test = program_test(program_id);
+    // A 1% swap fee, charged at every hop
+    let mut program_state = default_program_state(&program_id);
+    program_state.swap_fee_rate = 1;
+    add_program_state(&mut program_test, &program_id, &program_state);
+    let mints: Vec<Pubkey> = (0..=MAX_ROUTE_HOPS).map(|_| Pubkey::new_unique()).collect();
+    let pools = add_pool_chain(&mut program_test, &program_id, &mints, RESERVE);
+
+    let user = Keypair::new();
+    let (user_source, user_destination) = (Pubkey::new_unique(), Pubkey::new_unique());
+    add_token_account(&mut program_test, user_source, mints[0], user.pubkey(), AMOUNT_IN);
+    add_token_account(&mut program_test, user_destination, mints[MAX_ROUTE_HOPS], user.pubkey(), 0);
+
+    // What each hop should pay: every pool starts balanced, so each prices its input alike
+    let mut hop_amounts = vec![AMOUNT_IN];
+    for _ in 0..MAX_ROUTE_HOPS {
+        let hop_in = *hop_amounts.last().unwrap();
+        hop_amounts.push(calculate_pool_swap_output_bps(hop_in, RESERVE, RESERVE, 100).unwrap());
+    }
+    let amount_out = hop_amounts[MAX_ROUTE_HOPS];
+
+    let (mut banks_client, payer, _) = program_test.start().await;
+    let swap = routed_pool_swap_instruction(&program_id, &user.pubkey(), &pools, &user_source, &user_destination, AMOUNT_IN, amount_out);
+    let transaction = transaction(&mut banks_client, &payer, &[swap], &[&user]).await;
+    let outcome = banks_client.process_transaction_with_metadata(transaction).await.unwrap();
+    outcome.result.unwrap();
+
+    assert_eq!(token_balance(&mut banks_client, user_source).await, 0);
+    assert_eq!(token_balance(&mut banks_client, user_destination).await, amount_out);
+    // Each pool kept what it was paid and paid the next one straight from its reserve
+    for (hop, pool) in pools.iter().enumerate() {
+        assert_eq!(token_balance(&mut banks_client, pool.reserve_a).await, RESERVE + hop_amounts[hop]);
+        assert_eq!(token_balance(&mut banks_client, pool.reserve_b).await, RESERVE - hop_amounts[hop + 1]);
+    }
+
+    // SwapResult in the return data: amount in, amount out, then the first hop's output
+    let return_data = outcome.metadata.unwrap().return_data.unwrap();
+    assert_eq!(return_data.program_id, program_id);
+    assert_eq!(return_data.data[0..8], AMOUNT_IN.to_le_bytes());
+    assert_eq!(return_data.data[8..16], amount_out.to_le_bytes());
+    assert_eq!(return_data.data[16..24], hop_amounts[1].to_le_bytes());
+}
+
+#[tokio::test]
+async fn route_past_max_depth_is_rejected() {
+    let program_id = Pubkey::new_unique();
+    let mut program_test = program_test(program_id);
+    add_program_state(&mut program_test, &program_id, &default_program_state(&program_id));
+    let mints: Vec<Pubkey> = (0..=MAX_ROUTE_HOPS + 1).map(|_| Pubkey::new_unique()).collect();
+    let pools = add_pool_chain(&mut program_test, &program_id, &mints, RESERVE);
+
+    let user = Keypair::new();
+    let (user_source, user_destination) = (Pubkey::new_unique(), Pubkey::new_unique());
+    add_token_account(&mut program_test, user_source, mints[0], user.pubkey(), AMOUNT_IN);
+    add_token_account(&mut program_test, user_destination, mints[MAX_ROUTE_HOPS + 1], user.pubkey(), 0);
+
+    let (mut banks_client, payer, _) = program_test.start().await;
+    let swap = routed_pool_swap_instruction(&program_id, &user.pubkey(), &pools, &user_source, &user_destination, AMOUNT_IN, 1);
+    let transaction = transaction(&mut banks_client, &payer, &[swap], &[&user]).await;
+    let error = banks_client.process_transaction(transaction).await.unwrap_err().unwrap();
+    assert_eq!(error, program_error(MultiHubSwapError::InvalidRoute));
+    assert_eq!(token_balance(&mut banks_client, user_source).await, AMOUNT_IN);
+}
@@ -0,0 +1,43 @@
+//! Where a YOT to SOL swap (discriminator 9) takes its liquidity contribution from, as set by
+//! SetSellContributionSide: the SOL output by default, or the YOT input before pricing
+
+#![cfg(feature = "processor-multi-hub-swap")]
+// clippy.toml lets #[test] functions unwrap; the async tests and their helpers may too
+#![allow(clippy::unwrap_used)]
+
+mod common;
+
+use common::*;
+use multi_hub_swap::state::ProgramState;
+use multi_hub_swap_core::calculate_swap_output;
+use solana_sdk::signature::Signer;
+
+#[tokio::test]
+async fn sol_output_side_pays_the_user_the_rest_of_the_output() {
+    let sale = YotSale::new();
+    let (mut banks_client, created_rent) = sale.run().await;
+
+    // The whole input is priced, then a fifth of the SOL goes to the central wallet
+    let sol_out = calculate_swap_output(SALE_AMOUNT_IN, SALE_YOT_RESERVE, SALE_SOL_RESERVE).unwrap();
+    let contribution = sol_out * SALE_LP_RATE / 100;
+    assert_eq!(
+        banks_client.get_balance(sale.user.pubkey()).await.unwrap(),
+        SALE_WALLET_LAMPORTS + sol_out * (100 - SALE_LP_RATE) / 100 - created_rent,
+    );
+    assert_eq!(banks_client.get_balance(sale.program_state.liquidity_wallet).await.unwrap(), contribution);
+    assert_eq!(token_balance(&mut banks_client, sale.yot_pool()).await, SALE_YOT_RESERVE + SALE_AMOUNT_IN);
+}
+
+#[tokio::test]
+async fn yot_input_side_pays_the_user_the_whole_quote() {
+    let mut sale = YotSale::new();
+    sale.program_state.sell_contribution_side = ProgramState::SELL_CONTRIBUTION_YOT_INPUT;
+    let (mut banks_client, created_rent) = sale.run().await;
+
+    // A fifth of the input goes to the central wallet unsold; the rest is priced and paid in full
+    let contribution = SALE_AMOUNT_IN * SALE_LP_RATE / 100;
+    let sol_out = calculate_swap_output(SALE_AMOUNT_IN - contribution, SALE_YOT_RESERVE, SALE_SOL_RESERVE).unwrap();
+    assert_eq!(banks_client.get_balance(sale.user.pubkey()).await.unwrap(), SALE_WALLET_LAMPORTS + sol_out - created_rent);
+    assert_eq!(token_balance(&mut banks_client, sale.program_state.liquidity_wallet).await, contribution);
+    assert_eq!(token_balance(&mut banks_client, sale.yot_pool()).await, SALE_YOT_RESERVE + SALE_AMOUNT_IN - contribution);
+}
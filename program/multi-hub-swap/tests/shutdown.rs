@@ -0,0 +1,76 @@
+//! ExecuteShutdown (discriminator 77): only protocol-owned token accounts go to the shutdown
+//! recipient; accounts backing user funds stay where they are
+
+#![cfg(feature = "processor-multi-hub-swap")]
+// clippy.toml lets #[test] functions unwrap; the async tests and their helpers may too
+#![allow(clippy::unwrap_used)]
+
+mod common;
+
+use common::*;
+use multi_hub_swap::multi_hub_swap_complete::{ProgramTokenAccount, ProgramTokenAccountRegistry};
+use solana_sdk::{
+    instruction::{AccountMeta, Instruction},
+    pubkey::Pubkey,
+    signature::{Keypair, Signer},
+};
+
+const BALANCE: u64 = 5_000_000;
+
+#[tokio::test]
+async fn shutdown_drains_the_fee_vault_and_leaves_escrows_untouched() {
+    let (program_id, admin, recipient) = (Pubkey::new_unique(), Keypair::new(), Pubkey::new_unique());
+    let authority = program_authority_address(&program_id);
+    let mint = Pubkey::new_unique();
+    let mut program_state = default_program_state(&program_id);
+    program_state.admin = admin.pubkey();
+    program_state.yot_mint = Pubkey::new_unique();
+    program_state.shutdown_recipient = recipient;
+    program_state.shutdown_effective_at = 1;
+
+    let mut program_test = program_test(program_id);
+    add_program_state(&mut program_test, &program_id, &program_state);
+    add_wallet(&mut program_test, admin.pubkey(), 1_000_000_000);
+    add_mint(&mut program_test, mint, authority, 2 * BALANCE);
+
+    // A registered fee vault, and an order escrow the program authority also owns
+    let fee_vault = associated_token_address(&authority, &mint);
+    let escrow = Pubkey::new_unique();
+    let destination = associated_token_address(&recipient, &mint);
+    add_token_account(&mut program_test, fee_vault, mint, authority, BALANCE);
+    add_token_account(&mut program_test, escrow, mint, authority, BALANCE);
+    add_token_account(&mut program_test, destination, mint, recipient, 0);
+    let registry_address = Pubkey::find_program_address(&[b"token_accounts"], &program_id).0;
+    let registry = ProgramTokenAccountRegistry {
+        accounts: vec![ProgramTokenAccount {
+            mint,
+            token_account: fee_vault,
+            purpose: ProgramTokenAccountRegistry::PURPOSE_FEE_VAULT,
+        }],
+    };
+    let mut data = vec![0u8; ProgramTokenAccountRegistry::LEN];
+    registry.pack(&mut data).unwrap();
+    program_test.add_account(registry_address, account_with_data(data, program_id));
+
+    let (mut banks_client, payer, _) = program_test.start().await;
+    let shutdown = Instruction {
+        program_id,
+        accounts: vec![
+            AccountMeta::new_readonly(admin.pubkey(), true),
+            AccountMeta::new(program_state_address(&program_id), false),
+            AccountMeta::new_readonly(authority, false),
+            AccountMeta::new_readonly(spl_token::id(), false),
+            AccountMeta::new_readonly(registry_address, false),
+            AccountMeta::new(fee_vault, false),
+            AccountMeta::new(escrow, false),
+            AccountMeta::new(destination, false),
+        ],
+        data: vec![77],
+    };
+    let transaction = transaction(&mut banks_client, &payer, &[shutdown], &[&admin]).await;
+    banks_client.process_transaction(transaction).await.unwrap();
+
+    assert_eq!(token_balance(&mut banks_client, fee_vault).await, 0);
+    assert_eq!(token_balance(&mut banks_client, destination).await, BALANCE);
+    assert_eq!(token_balance(&mut banks_client, escrow).await, BALANCE);
+}
@@ -0,0 +1,149 @@
+//! RelayedYotToSolSwap (discriminator 65): the user's permit is read from the ed25519
+//! signature check just before the swap through the instructions sysvar
+
+#![cfg(feature = "processor-multi-hub-swap")]
+// clippy.toml lets #[test] functions unwrap; the async tests and their helpers may too
+#![allow(clippy::unwrap_used)]
+
+mod common;
+
+use common::*;
+use multi_hub_swap::multi_hub_swap_complete::{swap_permit_message, MultiHubSwapError, SWAP_PERMIT_LEN};
+use solana_program_test::BanksClient;
+use solana_sdk::{
+    ed25519_program,
+    instruction::{AccountMeta, Instruction, InstructionError},
+    pubkey::Pubkey,
+    signature::{Keypair, Signer},
+    system_program, sysvar,
+    transaction::TransactionError,
+};
+
+const AMOUNT_IN: u64 = 1_000_000;
+const MIN_AMOUNT_OUT: u64 = 1;
+/// Offsets into an ed25519 instruction's own data: a 16-byte header, then key, signature, message
+const PUBLIC_KEY_OFFSET: u16 = 16;
+const SIGNATURE_OFFSET: u16 = 48;
+const MESSAGE_OFFSET: u16 = 112;
+/// Instruction index meaning "this instruction" in the ed25519 offsets
+const THIS_INSTRUCTION: u16 = u16::MAX;
+
+/// Header of an ed25519 check of one signature, with every offset into instruction `index`
+fn ed25519_header(message_len: usize, index: u16) -> Vec<u8> {
+    let mut data = vec![1, 0];
+    for value in [SIGNATURE_OFFSET, index, PUBLIC_KEY_OFFSET, index, MESSAGE_OFFSET, message_len as u16, index] {
+        data.extend_from_slice(&value.to_le_bytes());
+    }
+    data
+}
+
+/// An ed25519 check of `signer`'s signature over `message`, all inside the instruction
+fn ed25519_instruction(signer: &Keypair, message: &[u8]) -> Instruction {
+    let mut data = ed25519_header(message.len(), THIS_INSTRUCTION);
+    data.extend_from_slice(signer.pubkey().as_ref());
+    data.extend_from_slice(signer.sign_message(message).as_ref());
+    data.extend_from_slice(message);
+    Instruction { program_id: ed25519_program::id(), accounts: vec![], data }
+}
+
+struct Relay {
+    program_id: Pubkey,
+    user: Keypair,
+}
+
+impl Relay {
+    fn new() -> Self {
+        Self { program_id: Pubkey::new_unique(), user: Keypair::new() }
+    }
+
+    async fn start(&self) -> (BanksClient, Keypair) {
+        let mut program_test = program_test(self.program_id);
+        add_program_state(&mut program_test, &self.program_id, &default_program_state(&self.program_id));
+        let (banks_client, payer, _) = program_test.start().await;
+        (banks_client, payer)
+    }
+
+    fn permit(&self, nonce: u64) -> [u8; SWAP_PERMIT_LEN] {
+        swap_permit_message(&self.program_id, &self.user.pubkey(), AMOUNT_IN, MIN_AMOUNT_OUT, nonce)
+    }
+
+    /// The relayed swap up to the swap accounts the permit check reads
+    fn swap_instruction(&self, relayer: &Pubkey, nonce: u64) -> Instruction {
+        let delegate = Pubkey::find_program_address(&[b"swap_delegate", self.user.pubkey().as_ref()], &self.program_id).0;
+        let mut data = vec![65];
+        data.extend_from_slice(&AMOUNT_IN.to_le_bytes());
+        data.extend_from_slice(&MIN_AMOUNT_OUT.to_le_bytes());
+        data.extend_from_slice(&nonce.to_le_bytes());
+        Instruction {
+            program_id: self.program_id,
+            accounts: vec![
+                AccountMeta::new(*relayer, true),
+                AccountMeta::new(delegate, false),
+                AccountMeta::new_readonly(sysvar::instructions::id(), false),
+                AccountMeta::new_readonly(self.user.pubkey(), false),
+                AccountMeta::new(program_state_address(&self.program_id), false),
+                AccountMeta::new_readonly(system_program::id(), false),
+            ],
+            data,
+        }
+    }
+
+    /// Run `instructions`, then the swap with nonce 0, and return the error
+    async fn swap_error(&self, instructions: &[Instruction]) -> TransactionError {
+        let (mut banks_client, payer) = self.start().await;
+        let mut instructions = instructions.to_vec();
+        instructions.push(self.swap_instruction(&payer.pubkey(), 0));
+        let relayed = transaction(&mut banks_client, &payer, &instructions, &[]).await;
+        banks_client.process_transaction(relayed).await.unwrap_err().unwrap()
+    }
+}
+
+fn swap_failure(index: u8, error: MultiHubSwapError) -> TransactionError {
+    TransactionError::InstructionError(index, InstructionError::Custom(error as u32))
+}
+
+#[tokio::test]
+async fn a_valid_permit_passes_the_signature_check() {
+    let relay = Relay::new();
+    let (mut banks_client, payer) = relay.start().await;
+
+    // Nonce 1 on a new delegate gets past the signature and stops at the nonce
+    let instructions = [ed25519_instruction(&relay.user, &relay.permit(1)), relay.swap_instruction(&payer.pubkey(), 1)];
+    let relayed = transaction(&mut banks_client, &payer, &instructions, &[]).await;
+    let error = banks_client.process_transaction(relayed).await.unwrap_err().unwrap();
+    assert_eq!(error, swap_failure(1, MultiHubSwapError::SwapPermitNonceMismatch));
+}
+
+#[tokio::test]
+async fn a_permit_signed_by_another_key_is_rejected() {
+    let relay = Relay::new();
+    let error = relay.swap_error(&[ed25519_instruction(&Keypair::new(), &relay.permit(0))]).await;
+    assert_eq!(error, swap_failure(1, MultiHubSwapError::InvalidSwapPermit));
+}
+
+#[tokio::test]
+async fn a_permit_for_another_swap_is_rejected() {
+    let relay = Relay::new();
+    let other_swap = swap_permit_message(&relay.program_id, &relay.user.pubkey(), AMOUNT_IN + 1, MIN_AMOUNT_OUT, 0);
+    let error = relay.swap_error(&[ed25519_instruction(&relay.user, &other_swap)]).await;
+    assert_eq!(error, swap_failure(1, MultiHubSwapError::InvalidSwapPermit));
+}
+
+#[tokio::test]
+async fn a_check_reading_another_instruction_is_rejected() {
+    let relay = Relay::new();
+    let permit = relay.permit(0);
+
+    // The runtime verifies the second check against the first one's data; the program only
+    // trusts a check whose key, signature and message are its own
+    let borrowed = Instruction { program_id: ed25519_program::id(), accounts: vec![], data: ed25519_header(permit.len(), 0) };
+    let error = relay.swap_error(&[ed25519_instruction(&relay.user, &permit), borrowed]).await;
+    assert_eq!(error, swap_failure(2, MultiHubSwapError::InvalidSwapPermit));
+}
+
+#[tokio::test]
+async fn a_swap_without_a_signature_check_is_rejected() {
+    let relay = Relay::new();
+    let error = relay.swap_error(&[]).await;
+    assert_eq!(error, swap_failure(0, MultiHubSwapError::InvalidSwapPermit));
+}
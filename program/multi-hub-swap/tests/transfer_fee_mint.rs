@@ -0,0 +1,105 @@
+//! Contribute (discriminator 2) with a Token-2022 YOT mint that charges a transfer fee
+
+#![cfg(feature = "processor-multi-hub-swap")]
+// clippy.toml lets #[test] functions unwrap; the async tests and their helpers may too
+#![allow(clippy::unwrap_used)]
+
+mod common;
+
+use common::*;
+use multi_hub_swap::multi_hub_swap_complete::LiquidityContribution;
+use solana_program_test::BanksClient;
+use solana_sdk::{
+    instruction::{AccountMeta, Instruction},
+    pubkey::Pubkey,
+    signature::{Keypair, Signer},
+    system_instruction, system_program,
+};
+use spl_token_2022::{
+    extension::{transfer_fee::instruction::initialize_transfer_fee_config, ExtensionType, StateWithExtensions},
+    instruction::{initialize_account3, initialize_mint2, mint_to},
+    state::{Account, Mint},
+};
+
+const AMOUNT: u64 = 1_000_000;
+/// 1% of every transfer is withheld in the destination account
+const TRANSFER_FEE_BPS: u16 = 100;
+
+/// Create `account` as a Token-2022 account of `mint` owned by `owner`
+fn token_account_instructions(payer: &Pubkey, account: &Pubkey, mint: &Pubkey, owner: &Pubkey, rent: u64) -> Vec<Instruction> {
+    let space = ExtensionType::try_calculate_account_len::<Account>(&[ExtensionType::TransferFeeAmount]).unwrap();
+    vec![
+        system_instruction::create_account(payer, account, rent, space as u64, &spl_token_2022::id()),
+        initialize_account3(&spl_token_2022::id(), account, mint, owner).unwrap(),
+    ]
+}
+
+async fn token_2022_balance(banks_client: &mut BanksClient, key: Pubkey) -> u64 {
+    let account = banks_client.get_account(key).await.unwrap().unwrap();
+    StateWithExtensions::<Account>::unpack(&account.data).unwrap().base.amount
+}
+
+#[tokio::test]
+async fn contribution_credits_what_arrives_after_the_transfer_fee() {
+    let program_id = Pubkey::new_unique();
+    let (mint, user, user_yot, central_yot) = (Keypair::new(), Keypair::new(), Keypair::new(), Keypair::new());
+    let mut program_state = default_program_state(&program_id);
+    program_state.yot_mint = mint.pubkey();
+    program_state.yos_mint = Pubkey::new_unique();
+    program_state.liquidity_wallet = Pubkey::new_unique();
+    let user_yos = associated_token_address(&user.pubkey(), &program_state.yos_mint);
+
+    let mut program_test = program_test(program_id);
+    add_program_state(&mut program_test, &program_id, &program_state);
+    add_mint(&mut program_test, program_state.yos_mint, program_authority_address(&program_id), 0);
+    add_token_account(&mut program_test, user_yos, program_state.yos_mint, user.pubkey(), 0);
+    add_wallet(&mut program_test, user.pubkey(), 1_000_000_000);
+    let (mut banks_client, payer, _) = program_test.start().await;
+    let rent = banks_client.get_rent().await.unwrap();
+
+    // The fee mint, the user's account holding AMOUNT and the central wallet's empty account
+    let mint_space = ExtensionType::try_calculate_account_len::<Mint>(&[ExtensionType::TransferFeeConfig]).unwrap();
+    let account_rent = rent.minimum_balance(ExtensionType::try_calculate_account_len::<Account>(&[ExtensionType::TransferFeeAmount]).unwrap());
+    let mut setup = vec![
+        system_instruction::create_account(&payer.pubkey(), &mint.pubkey(), rent.minimum_balance(mint_space), mint_space as u64, &spl_token_2022::id()),
+        initialize_transfer_fee_config(&spl_token_2022::id(), &mint.pubkey(), None, None, TRANSFER_FEE_BPS, u64::MAX).unwrap(),
+        initialize_mint2(&spl_token_2022::id(), &mint.pubkey(), &payer.pubkey(), None, 9).unwrap(),
+    ];
+    setup.extend(token_account_instructions(&payer.pubkey(), &user_yot.pubkey(), &mint.pubkey(), &user.pubkey(), account_rent));
+    setup.extend(token_account_instructions(&payer.pubkey(), &central_yot.pubkey(), &mint.pubkey(), &program_state.liquidity_wallet, account_rent));
+    setup.push(mint_to(&spl_token_2022::id(), &mint.pubkey(), &user_yot.pubkey(), &payer.pubkey(), &[], AMOUNT).unwrap());
+    let setup = transaction(&mut banks_client, &payer, &setup, &[&mint, &user_yot, &central_yot]).await;
+    banks_client.process_transaction(setup).await.unwrap();
+
+    let contribution = Pubkey::find_program_address(&[b"liq", user.pubkey().as_ref()], &program_id).0;
+    let pda = |seeds: &[&[u8]]| Pubkey::find_program_address(seeds, &program_id).0;
+    let mut data = vec![2];
+    data.extend_from_slice(&AMOUNT.to_le_bytes());
+    let contribute = Instruction {
+        program_id,
+        accounts: vec![
+            AccountMeta::new(user.pubkey(), true),
+            AccountMeta::new(user_yot.pubkey(), false),
+            AccountMeta::new(central_yot.pubkey(), false),
+            AccountMeta::new(contribution, false),
+            AccountMeta::new_readonly(spl_token_2022::id(), false),
+            AccountMeta::new_readonly(system_program::id(), false),
+            AccountMeta::new(program_state.yos_mint, false),
+            AccountMeta::new(user_yos, false),
+            AccountMeta::new_readonly(program_state_address(&program_id), false),
+            AccountMeta::new(pda(&[b"global_contribution"]), false),
+            AccountMeta::new(pda(&[b"history", user.pubkey().as_ref()]), false),
+            AccountMeta::new_readonly(mint.pubkey(), false),
+        ],
+        data,
+    };
+    let contribute = transaction(&mut banks_client, &payer, &[contribute], &[&user]).await;
+    banks_client.process_transaction(contribute).await.unwrap();
+
+    // The withheld fee never reaches the central wallet, so it is not credited either
+    let received = AMOUNT - AMOUNT * u64::from(TRANSFER_FEE_BPS) / 10_000;
+    assert_eq!(token_2022_balance(&mut banks_client, user_yot.pubkey()).await, 0);
+    assert_eq!(token_2022_balance(&mut banks_client, central_yot.pubkey()).await, received);
+    let account = banks_client.get_account(contribution).await.unwrap().unwrap();
+    assert_eq!(LiquidityContribution::unpack(&account.data).unwrap().contributed_amount, received);
+}